@@ -83,6 +83,9 @@ pub fn generate_bindings<P: AsRef<Path>>(path: P) {
         .header("libexif/exif-mnote-data.h")
         .header("libexif/exif-tag.h")
         .header("libexif/exif-utils.h")
+        // Tags read from files aren't limited to the ones libexif knows about, and the GPS tags
+        // are only defined as constants, so ExifTag has to accept any value
+        .newtype_enum("ExifTag")
         .rustified_enum("Exif.*")
         .clang_arg(format!("-I{}", path.join("include").display()))
         .generate()
@@ -110,6 +113,13 @@ fn main() {
     let dst = Config::new(libexif_dir).enable("static", None).build();
     // let dst = Config::new(libexif_dir).build();
 
+    // libexif's log messages come with a va_list, which is formatted in C, see src/log.c
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    cc::Build::new()
+        .file(Path::new(&manifest_dir).join("src/log.c"))
+        .include(dst.join("include"))
+        .compile("exif_rs_log");
+
     generate_bindings(out_dir);
     println!(
         "cargo:rustc-link-search=native={}",
@@ -117,4 +127,5 @@ fn main() {
     );
     println!("cargo:rustc-link-lib=exif");
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/log.c");
 }
@@ -20,11 +20,13 @@ fn dump_exif<P: AsRef<Path>>(file_name: P) -> Result<(), libexif::ExifError> {
             println!("[{:=>31}{:=>46}]", format!(" {:?} ", content.ifd()), "");
 
             for entry in content.entries() {
-                println!(
-                    " {:<30} = {}",
-                    entry.tag().title(content.ifd()),
-                    entry.text_value()?
-                );
+                let title = if entry.is_known_tag() {
+                    entry.tag().title(content.ifd()).to_string()
+                } else {
+                    format!("Unknown tag 0x{:04x}", entry.tag().id())
+                };
+
+                println!(" {:<30} = {}", title, entry.text_value()?);
             }
         }
     }
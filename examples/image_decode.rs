@@ -0,0 +1,16 @@
+use std::env;
+
+fn main() {
+    for arg in env::args_os().skip(1) {
+        let bytes = std::fs::read(&arg).unwrap();
+
+        // Decode the pixels and read the EXIF data from the same buffer
+        let image = image::load_from_memory(&bytes).unwrap();
+        let exif = libexif::from_image_bytes(&bytes).unwrap();
+
+        println!("{:?}: {}x{}", arg, image.width(), image.height());
+        for (ifd, title) in exif.tag_names() {
+            println!("  {:?} {}", ifd, title);
+        }
+    }
+}
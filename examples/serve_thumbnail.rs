@@ -0,0 +1,41 @@
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+
+/// Serve the thumbnail of the given JPEG at http://127.0.0.1:8080/.
+fn main() {
+    let path = env::args_os()
+        .nth(1)
+        .expect("usage: serve_thumbnail <file.jpg>");
+    let data = libexif::Data::open(&path).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+
+    for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+
+        // Skip the request, every path gets the thumbnail
+        let mut request = BufReader::new(&stream);
+        let mut line = String::new();
+        while request.read_line(&mut line).unwrap() > 2 {
+            line.clear();
+        }
+
+        // The thumbnail is written straight from the EXIF data, without copying it
+        match data.thumbnail() {
+            Some(thumbnail) => {
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    thumbnail.len()
+                )
+                .unwrap();
+                stream.write_all(thumbnail).unwrap();
+            }
+            None => {
+                stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
 use crate::bindings::*;
+use crate::error::ExifError;
 
 use crate::internal::*;
 
@@ -35,6 +39,16 @@ impl ToLibExif<ExifByteOrder> for ByteOrder {
 }
 
 /// Defines the encoding used to represent EXIF data.
+///
+/// `Display` and `FromStr` use the names `chunky`, `planar`, `ycc`, `compressed` and `unknown`.
+///
+/// # Example
+/// ```rust
+/// use libexif::DataEncoding;
+/// assert_eq!("ycc".parse::<DataEncoding>().unwrap(), DataEncoding::Ycc);
+/// assert_eq!(DataEncoding::Compressed.to_string(), "compressed");
+/// assert!("jpeg".parse::<DataEncoding>().is_err());
+/// ```
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum DataEncoding {
     Chunky,
@@ -68,6 +82,43 @@ impl ToLibExif<ExifDataType> for DataEncoding {
     }
 }
 
+/// Implement `Display` and `FromStr` with the given names of the variants, e.g., for reading
+/// enums from configuration files. Names are parsed ignoring ASCII case.
+macro_rules! names {
+    ($type:ident { $($variant:ident => $name:literal,)* }) => {
+        impl Display for $type {
+            fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+                fmt.write_str(match *self {
+                    $($type::$variant => $name,)*
+                })
+            }
+        }
+
+        impl FromStr for $type {
+            type Err = ExifError;
+
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                $(if name.eq_ignore_ascii_case($name) {
+                    return Ok($type::$variant);
+                })*
+
+                Err(ExifError::UnknownName {
+                    kind: stringify!($type),
+                    name: name.to_string(),
+                })
+            }
+        }
+    };
+}
+
+names!(DataEncoding {
+    Chunky => "chunky",
+    Planar => "planar",
+    Ycc => "ycc",
+    Compressed => "compressed",
+    Unknown => "unknown",
+});
+
 /// Options that affect the behavior of [`Data`](struct.Data.html).
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum DataOption {
@@ -147,6 +198,85 @@ impl DataType {
     pub(crate) fn size(&self) -> usize {
         unsafe { exif_format_get_size(self.to_libexif()) as usize }
     }
+
+    /// Name of the Rust type a component of this type is decoded to in a
+    /// [`Value`](enum.Value.html), `String` for text which isn't split into components.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::DataType;
+    /// assert_eq!(DataType::U16.rust_type_name(), "u16");
+    /// assert_eq!(DataType::URational.rust_type_name(), "Rational<u32>");
+    /// assert_eq!(DataType::Text.rust_type_name(), "String");
+    /// ```
+    pub fn rust_type_name(&self) -> &'static str {
+        match *self {
+            DataType::Text => "String",
+            DataType::U8 | DataType::Undefined => "u8",
+            DataType::I8 => "i8",
+            DataType::U16 => "u16",
+            DataType::I16 => "i16",
+            DataType::U32 => "u32",
+            DataType::I32 => "i32",
+            DataType::URational => "Rational<u32>",
+            DataType::IRational => "Rational<i32>",
+        }
+    }
+
+    /// Number of bytes a single component of this type takes up in an entry, one character
+    /// for text.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::DataType;
+    /// assert_eq!(DataType::U32.element_size(), 4);
+    /// assert_eq!(DataType::IRational.element_size(), 8);
+    /// ```
+    pub fn element_size(&self) -> usize {
+        match *self {
+            DataType::Text | DataType::U8 | DataType::I8 | DataType::Undefined => 1,
+            DataType::U16 | DataType::I16 => 2,
+            DataType::U32 | DataType::I32 => 4,
+            DataType::URational | DataType::IRational => 8,
+        }
+    }
+
+    /// Whether every value of this type can be represented by the other type without loss,
+    /// i.e., whether an integer type can be widened to the other one.
+    ///
+    /// Every type fits in itself, other than that only integer types fit in larger integer
+    /// types that hold their whole range.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::DataType;
+    /// assert!(DataType::U8.fits_in(DataType::U16));
+    /// assert!(DataType::U16.fits_in(DataType::I32));
+    /// assert!(!DataType::U8.fits_in(DataType::I8));
+    /// assert!(!DataType::I16.fits_in(DataType::U32));
+    /// ```
+    pub fn fits_in(&self, other: DataType) -> bool {
+        use DataType::*;
+
+        *self == other
+            || matches!(
+                (*self, other),
+                (U8, U16 | I16 | U32 | I32) | (I8, I16 | I32) | (U16, U32 | I32) | (I16, I32)
+            )
+    }
+}
+
+impl DataType {
+    /// Same as `from_libexif()` but fails on the formats the crate doesn't model instead of
+    /// treating them as `Undefined`.
+    pub(crate) fn try_from_libexif(format: ExifFormat) -> Result<Self, ExifError> {
+        let data_type = DataType::from_libexif(format);
+        if data_type.to_libexif() != format {
+            return Err(ExifError::UnsupportedFormat(format));
+        }
+
+        Ok(data_type)
+    }
 }
 
 impl FromLibExif<ExifFormat> for DataType {
@@ -203,7 +333,10 @@ pub enum IFD {
     /// IFD contents contain tags used for interoperability.
     Interoperability,
 
-    /// Unknown IFD type.
+    /// Not an actual IFD but the number of IFDs, libexif uses it as a sentinel for unknown IFDs.
+    ///
+    /// EXIF data never has a `Count` IFD, [`Data::contents()`](struct.Data.html#method.contents)
+    /// doesn't return it and accessing entries in it fails with `InvalidIfd`.
     Count,
 }
 
@@ -234,7 +367,23 @@ impl ToLibExif<ExifIfd> for IFD {
 }
 
 /// Requirement specificatoins for standard EXIF tags.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+///
+/// Support levels are ordered by importance, i.e.,
+/// `Required < Optional < NotAllowed < Unknown`, so sorting tags by their support level puts
+/// the mandatory ones first.
+///
+/// `Display` and `FromStr` use libexif's names: `mandatory`, `optional`, `notrecorded` and
+/// `unknown`.
+///
+/// # Example
+/// ```rust
+/// use libexif::SupportLevel;
+/// assert_eq!("notrecorded".parse::<SupportLevel>().unwrap(), SupportLevel::NotAllowed);
+/// assert_eq!("Mandatory".parse::<SupportLevel>().unwrap(), SupportLevel::Required);
+/// assert_eq!(SupportLevel::Optional.to_string(), "optional");
+/// assert!("required".parse::<SupportLevel>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum SupportLevel {
     /// EXIF tag is mandatory for the given IFD.
     Required,
@@ -249,6 +398,25 @@ pub enum SupportLevel {
     Unknown,
 }
 
+impl SupportLevel {
+    /// Returns `true` if the tag is mandatory.
+    pub fn is_required(&self) -> bool {
+        *self == SupportLevel::Required
+    }
+
+    /// Returns `true` if the tag may be recorded, i.e., it's either required or optional.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, SupportLevel::Required | SupportLevel::Optional)
+    }
+}
+
+names!(SupportLevel {
+    Required => "mandatory",
+    Optional => "optional",
+    NotAllowed => "notrecorded",
+    Unknown => "unknown",
+});
+
 impl FromLibExif<ExifSupportLevel> for SupportLevel {
     fn from_libexif(support_level: ExifSupportLevel) -> Self {
         match support_level {
@@ -3,12 +3,17 @@ use std::slice;
 use crate::bindings::*;
 
 use crate::bits::*;
-use crate::entry::Entry;
+use crate::entry::{Entry, EntryMut};
 use crate::internal::*;
+use crate::mem::Allocator;
+use crate::reader::Charset;
+use crate::value::Value;
 
 /// Container for all EXIF data in a single [IFD](enum.IFD.html).
 pub struct Content<'a> {
     inner: &'a mut ExifContent,
+    charset: Charset,
+    mem: Option<&'a Allocator>,
 }
 
 impl<'a> Content<'a> {
@@ -17,6 +22,23 @@ impl<'a> Content<'a> {
         IFD::from_libexif(unsafe { exif_content_get_ifd(self.inner as *const _ as *mut _) })
     }
 
+    /// Return the position of the content in the `ifd` array of libexif's `ExifData`, which is
+    /// the value of the content's `ExifIfd`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifIfd};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// for content in data.contents() {
+    ///     if content.ifd_index() == ExifIfd::EXIF_IFD_GPS as usize {
+    ///         println!("{} GPS entries", content.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn ifd_index(&self) -> usize {
+        unsafe { exif_content_get_ifd(self.inner as *const _ as *mut _) as usize }
+    }
+
     /// Return the number of [entries](struct.Entry.html) in the IFD.
     pub fn len(&self) -> usize {
         self.inner.count as usize
@@ -26,6 +48,35 @@ impl<'a> Content<'a> {
         self.inner.count == 0
     }
 
+    /// Return the entry with the given tag in the IFD, or `None` when there's none.
+    pub fn get_entry(&self, tag: impl Into<ExifTag>) -> Option<Entry> {
+        let entry = unsafe { exif_content_get_entry(self.inner as *const _ as *mut _, tag.into()) };
+        if entry.is_null() {
+            return None;
+        }
+
+        Some(Entry::from_libexif(unsafe { &mut *entry }).with_charset(self.charset))
+    }
+
+    /// Return the value of the entry with the given tag in the IFD, decoded with the byte order
+    /// of the EXIF data, see [`Entry::value_auto()`](struct.Entry.html#method.value_auto).
+    ///
+    /// `None` is returned when the IFD has no such entry or it can't be decoded.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// for content in data.contents() {
+    ///     if let Some(value) = content.get_value(ExifTag::EXIF_TAG_X_RESOLUTION) {
+    ///         println!("{:?}: {:?}", content.ifd(), value);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_value(&self, tag: impl Into<ExifTag>) -> Option<Value> {
+        self.get_entry(tag)?.value_auto().ok()
+    }
+
     /// Iterate over the [entries](struct.Entry.html) in the IFD.
     pub fn entries(&self) -> impl ExactSizeIterator<Item = Entry> {
         Entries {
@@ -33,19 +84,64 @@ impl<'a> Content<'a> {
                 slice::from_raw_parts(self.inner.entries, self.inner.count as usize)
             },
             index: 0,
+            charset: self.charset,
         }
     }
+
+    /// Iterate over the [entries](struct.Entry.html) in the IFD, allowing their values to be
+    /// replaced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, DataType, Rational, Value};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    ///
+    /// for mut content in data.contents() {
+    ///     for mut entry in content.entries_mut() {
+    ///         if entry.data_type() == DataType::URational && entry.components() == 1 {
+    ///             entry.set_value(Value::URational(vec![Rational(1, 1)])).unwrap();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn entries_mut(&mut self) -> impl ExactSizeIterator<Item = EntryMut<'_>> {
+        Entries {
+            entries: unsafe {
+                slice::from_raw_parts(self.inner.entries, self.inner.count as usize)
+            },
+            index: 0,
+            charset: self.charset,
+        }
+        .map(|entry| EntryMut::new(entry, self.mem))
+    }
+
+    /// Use the given character set when decoding the text of `ASCII` tags.
+    pub(crate) fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Use the allocator of the data the content belongs to when entries are resized.
+    pub(crate) fn with_allocator(mut self, allocator: &'a Allocator) -> Self {
+        self.mem = Some(allocator);
+        self
+    }
 }
 
 impl<'a> FromLibExif<&'a mut ExifContent> for Content<'a> {
     fn from_libexif(content: &'a mut ExifContent) -> Content<'a> {
-        Content { inner: content }
+        Content {
+            inner: content,
+            charset: Charset::default(),
+            mem: None,
+        }
     }
 }
 
 struct Entries<'a> {
     entries: &'a [*mut ExifEntry],
     index: usize,
+    charset: Charset,
 }
 
 impl<'a> Iterator for Entries<'a> {
@@ -56,7 +152,7 @@ impl<'a> Iterator for Entries<'a> {
             let entry = self.entries[self.index];
             self.index += 1;
 
-            Some(Entry::from_libexif(unsafe { &mut *entry }))
+            Some(Entry::from_libexif(unsafe { &mut *entry }).with_charset(self.charset))
         } else {
             None
         }
@@ -1,30 +1,72 @@
-use std::fs::File;
-use std::io;
+use std::collections::{HashMap, HashSet};
 use std::io::prelude::*;
 use std::path::Path;
 use std::slice;
 
+use libc::c_uint;
+
 use crate::bindings::*;
 use crate::bits::*;
 use crate::content::Content;
+use crate::diff::TagChange;
 use crate::entry::Entry;
 use crate::error::ExifError;
 use crate::internal::*;
-use crate::loader::Loader;
+use crate::jpeg::{self, Scan};
+use crate::log::WarningLog;
+use crate::mem::Allocator;
+use crate::reader::{invalid_data, Charset, DataReader, ParseStats};
+use crate::tag::Tag;
+use crate::tiff::{IfdOffsetInfo, Tiff};
 use crate::value::Value;
+use crate::xp;
 
 pub const EXIF_HEADER: [u8; 4] = [0xff, 0xd8, 0xff, 0xe1];
 pub const JPEG_HEADER: [u8; 4] = [0xff, 0xd8, 0xff, 0xe0];
 
+/// Layout of the EXIF data once serialized, see
+/// [`Data::exif_block_info()`](struct.Data.html#method.exif_block_info).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExifBlockInfo {
+    /// Number of bytes of the block, starting with the `Exif\0\0` identifier.
+    pub size: usize,
+
+    /// Position of the TIFF header in the block, right after the identifier.
+    pub tiff_header_offset: usize,
+
+    /// Byte order given by the `II` or `MM` marker of the TIFF header.
+    pub byte_order: ByteOrder,
+}
+
+impl ExifBlockInfo {
+    /// Whether the block fits in a single JPEG APP1 segment, whose length field counts its own
+    /// two bytes.
+    pub fn fits_in_app1(&self) -> bool {
+        self.size + 2 <= u16::MAX as usize
+    }
+}
+
 /// Container for all EXIF data found in an image.
 pub struct Data {
     inner: &'static mut ExifData,
+    charset: Charset,
+    tiff_header_offset: Option<usize>,
+    mem: Allocator,
+    source: Option<Vec<u8>>,
+    overrides: HashMap<(IFD, Tag), Value>,
+    options: HashSet<DataOption>,
 }
 
 impl FromLibExif<*mut ExifData> for Data {
     fn from_libexif(ptr: *mut ExifData) -> Data {
         Data {
             inner: unsafe { &mut *ptr },
+            charset: Charset::default(),
+            tiff_header_offset: None,
+            mem: Allocator::default(),
+            source: None,
+            overrides: HashMap::new(),
+            options: default_options(),
         }
     }
 }
@@ -53,33 +95,483 @@ impl Data {
     /// Create an empty  EXIF data
     pub fn new() -> Self {
         let inner = unsafe { &mut *exif_data_new() };
-        Self { inner }
+        Self {
+            inner,
+            charset: Charset::default(),
+            tiff_header_offset: None,
+            mem: Allocator::default(),
+            source: None,
+            overrides: HashMap::new(),
+            options: default_options(),
+        }
+    }
+
+    /// Create an empty EXIF data whose memory is managed by the given allocator.
+    ///
+    /// The entries created by [`set_entry()`](#method.set_entry) use the allocator too.
+    pub fn new_with_allocator(allocator: Allocator) -> Result<Self, ExifError> {
+        let ptr = unsafe { exif_data_new_mem(allocator.as_ptr()) };
+        if ptr.is_null() {
+            return Err(ExifError::ExifDataNull);
+        }
+
+        Ok(Data::from_libexif(ptr).with_allocator(allocator))
+    }
+
+    /// Use the given allocator, which must be the one the data was created with.
+    pub(crate) fn with_allocator(mut self, allocator: Allocator) -> Self {
+        self.mem = allocator;
+        self
+    }
+
+    /// Use the given character set when decoding the text of `ASCII` tags.
+    pub(crate) fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Record where the TIFF header of the data was found in the source file.
+    pub(crate) fn with_tiff_header_offset(mut self, offset: Option<usize>) -> Self {
+        self.tiff_header_offset = offset;
+        self
+    }
+
+    /// Keep the contents of the file the data was parsed from.
+    pub(crate) fn with_source(mut self, source: Vec<u8>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Return the position of the TIFF header (`II*` or `MM*`, right after the `Exif\0\0`
+    /// signature) in the file the data was parsed from.
+    ///
+    /// Offsets stored in the EXIF data are relative to this position. `None` is returned when
+    /// the data wasn't parsed from a JPEG, e.g., from a bare EXIF block.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// assert_eq!(data.tiff_header_offset(), Some(12));
+    /// ```
+    pub fn tiff_header_offset(&self) -> Option<usize> {
+        self.tiff_header_offset
+    }
+
+    /// Serialize the EXIF data without writing it anywhere to tell how large the block is and
+    /// its byte order, e.g., to size the APP1 segment or the container chunk before muxing the
+    /// data into a file.
+    ///
+    /// Overrides set with [`set_override()`](#method.set_override) are only applied when the
+    /// data is written, so they aren't accounted for. `None` is returned when libexif fails to
+    /// serialize the data.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Data};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let info = data.exif_block_info().unwrap();
+    /// assert_eq!(info.tiff_header_offset, 6);
+    /// assert_eq!(info.byte_order, ByteOrder::BigEndian);
+    /// assert!(info.fits_in_app1());
+    /// ```
+    pub fn exif_block_info(&self) -> Option<ExifBlockInfo> {
+        let block = self.serialize().ok()?;
+        let tiff_header_offset = jpeg::EXIF_SIGNATURE.len();
+        let byte_order = match block.get(tiff_header_offset..tiff_header_offset + 2)? {
+            b"II" => ByteOrder::LittleEndian,
+            b"MM" => ByteOrder::BigEndian,
+            _ => return None,
+        };
+
+        Some(ExifBlockInfo {
+            size: block.len(),
+            tiff_header_offset,
+            byte_order,
+        })
+    }
+
+    /// Describe where the IFDs and entries are in the file the data was read from, with the
+    /// offset of each IFD, the raw offset of the IFD following it, and the position and length of
+    /// the value each entry points at.
+    ///
+    /// This is meant for debugging broken offsets, e.g., when tags are missing after parsing.
+    /// Positions are in the file, the raw offsets stored in the EXIF data are relative to
+    /// [`tiff_header_offset()`](#method.tiff_header_offset). The file is only available when the
+    /// data was read with [`open_retaining()`](#method.open_retaining), an empty list is
+    /// returned otherwise.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, IFD};
+    /// let data = Data::open_retaining("assets/file.jpg").unwrap();
+    /// let offsets = data.dump_offsets();
+    /// assert_eq!(offsets[0].ifd, IFD::Image);
+    /// assert_eq!(offsets[0].offset, 20);
+    /// assert_eq!(offsets[0].next_ifd, Some(0));
+    ///
+    /// // XResolution, a rational stored out of line
+    /// let x_resolution = &offsets[0].entries[0];
+    /// assert_eq!(x_resolution.tag, 0x011a);
+    /// assert_eq!(x_resolution.value_offset, Some(74));
+    /// assert_eq!(x_resolution.value_length, 8);
+    ///
+    /// assert!(offsets.iter().any(|info| info.ifd == IFD::EXIF));
+    /// assert!(Data::open("assets/file.jpg").unwrap().dump_offsets().is_empty());
+    /// ```
+    pub fn dump_offsets(&self) -> Vec<IfdOffsetInfo> {
+        let base = self.tiff_header_offset.unwrap_or(0);
+        self.source
+            .as_deref()
+            .and_then(|source| Tiff::new(source.get(base..)?))
+            .map(|tiff| tiff.ifd_offsets(base))
+            .unwrap_or_default()
     }
 
     /// Construct a new EXIF data container with EXIF data from a JPEG file.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Data, ExifError> {
-        let mut file = File::open(path)?;
-        let mut loader = Loader::new();
-        let mut buffer = Vec::<u8>::with_capacity(1024);
+        DataReader::new().open(path)
+    }
 
-        loop {
-            let read_buf =
-                unsafe { slice::from_raw_parts_mut(buffer.as_mut_ptr(), buffer.capacity()) };
+    /// Construct a new EXIF data container with EXIF data read from a reader, calling
+    /// `progress` with the number of bytes read so far after each chunk, see the same method of
+    /// [`DataReader`](struct.DataReader.html).
+    pub fn from_reader_with_progress<R: Read>(
+        reader: R,
+        progress: impl FnMut(usize),
+    ) -> Result<Data, ExifError> {
+        DataReader::new().from_reader_with_progress(reader, progress)
+    }
 
-            let len = file.read(read_buf)?;
+    /// Construct a new EXIF data container with EXIF data from a JPEG file, reading the file
+    /// into the given buffer, see the same method of [`DataReader`](struct.DataReader.html).
+    pub fn open_using_buffer<P: AsRef<Path>>(
+        path: P,
+        buffer: &mut Vec<u8>,
+    ) -> Result<Data, ExifError> {
+        DataReader::new().open_using_buffer(path, buffer)
+    }
 
-            unsafe {
-                buffer.set_len(len);
+    /// Construct a new EXIF data container with EXIF data from a JPEG file, also returning
+    /// statistics about reading it, see the same method of
+    /// [`DataReader`](struct.DataReader.html).
+    pub fn open_with_stats<P: AsRef<Path>>(path: P) -> Result<(Data, ParseStats), ExifError> {
+        DataReader::new().open_with_stats(path)
+    }
+
+    /// Same as [`open()`](#method.open) but the contents of the file are kept, so the edited
+    /// data can be written with [`write_back()`](#method.write_back) without reading the file
+    /// again.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let mut data = Data::open_retaining("assets/file.jpg").unwrap();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![1]), order)
+    ///     .unwrap();
+    /// data.write_back("/tmp/written_back.jpg").unwrap();
+    /// ```
+    pub fn open_retaining<P: AsRef<Path>>(path: P) -> Result<Data, ExifError> {
+        DataReader::new().open_retaining(path)
+    }
+
+    /// Same as [`open()`](#method.open) but also returns the warnings libexif logged while
+    /// parsing the data, e.g., about corrupt entries or maker notes it doesn't understand.
+    ///
+    /// # Example
+    /// ```rust
+    /// let (data, warnings) = libexif::Data::open_collecting_warnings("assets/file.jpg").unwrap();
+    /// for warning in &warnings {
+    ///     eprintln!("{}", warning);
+    /// }
+    /// ```
+    ///
+    /// An APP1 segment whose length is too short for the EXIF data it holds is reported too,
+    /// libexif only reads the declared length:
+    /// ```rust
+    /// let mut jpeg = std::fs::read("assets/file.jpg").unwrap();
+    /// jpeg[4..6].copy_from_slice(&250u16.to_be_bytes());
+    /// let path = std::env::temp_dir().join("libexif-short-app1.jpg");
+    /// std::fs::write(&path, jpeg).unwrap();
+    ///
+    /// let (_, warnings) = libexif::Data::open_collecting_warnings(&path).unwrap();
+    /// assert!(warnings.iter().any(|warning| warning.starts_with("APP1 segment")));
+    /// ```
+    pub fn open_collecting_warnings<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Data, Vec<String>), ExifError> {
+        let log = WarningLog::new()?;
+        let data = DataReader::new().open_with_log(path, Some(&log))?;
+
+        Ok((data, log.into_messages()))
+    }
+
+    /// Construct a new EXIF data container with EXIF data from a HEIF file, e.g., an iPhone's
+    /// HEIC photo.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Data;
+    /// // A box with its size and type
+    /// fn boxed(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    ///     [&(8 + body.len() as u32).to_be_bytes()[..], kind, body].concat()
+    /// }
+    ///
+    /// // The Exif item: the offset of the TIFF header, then a TIFF block with an orientation
+    /// let item = b"\0\0\0\0II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0\x06\0\0\0\0\0\0\0";
+    /// let ftyp = boxed(b"ftyp", b"heic\0\0\0\0mif1heic");
+    /// // A meta box with the Exif item, stored at the offset in the file or in the idat box
+    /// let meta = |construction_method: u8, offset: u32, idat: &[u8]| {
+    ///     let infe = boxed(b"infe", b"\x02\0\0\0\0\x01\0\0Exif\0");
+    ///     let iinf = boxed(b"iinf", &[&b"\0\0\0\0\0\x01"[..], &infe].concat());
+    ///     let iloc = [
+    ///         &b"\x01\0\0\0\x44\0\0\x01\0\x01\0"[..],
+    ///         &[construction_method],
+    ///         b"\0\0\0\x01",
+    ///         &offset.to_be_bytes(),
+    ///         &(item.len() as u32).to_be_bytes(),
+    ///     ]
+    ///     .concat();
+    ///     let body = [&b"\0\0\0\0"[..], &iinf, &boxed(b"iloc", &iloc), &boxed(b"idat", idat)];
+    ///     boxed(b"meta", &body.concat())
+    /// };
+    ///
+    /// let path = std::env::temp_dir().join("libexif-open-heif.heic");
+    ///
+    /// // The item is in an mdat box after the meta box
+    /// let offset = ftyp.len() + meta(0, 0, b"").len() + 8;
+    /// let file = [ftyp.clone(), meta(0, offset as u32, b""), boxed(b"mdat", item)].concat();
+    /// std::fs::write(&path, file).unwrap();
+    /// assert_eq!(Data::open_heif(&path).unwrap().orientation(), Some(6));
+    ///
+    /// // The item is in the idat box
+    /// std::fs::write(&path, [ftyp, meta(1, 0, item)].concat()).unwrap();
+    /// assert_eq!(Data::open_heif(&path).unwrap().orientation(), Some(6));
+    /// ```
+    pub fn open_heif<P: AsRef<Path>>(path: P) -> Result<Data, ExifError> {
+        DataReader::new().open_heif(path)
+    }
+
+    /// Construct a new EXIF data container with EXIF data from a WebP file.
+    ///
+    /// Both lossy and lossless WebP files are supported, as long as they use the extended
+    /// layout with an `EXIF` chunk.
+    pub fn open_webp<P: AsRef<Path>>(path: P) -> Result<Data, ExifError> {
+        DataReader::new().open_webp(path)
+    }
+
+    /// Construct a new EXIF data container with EXIF data from the contents of a WebP file, see
+    /// [`open_webp()`](#method.open_webp).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifError};
+    /// // A WebP without any chunk
+    /// let webp = b"RIFF\x04\0\0\0WEBP";
+    /// assert!(matches!(Data::from_webp_bytes(webp), Err(ExifError::InvalidWebp(_))));
+    ///
+    /// // A chunk with its FourCC and size, odd-sized payloads are followed by a padding byte
+    /// fn chunk(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    ///     let padding: &[u8] = if payload.len() % 2 == 1 { b"\0" } else { b"" };
+    ///     [fourcc, &(payload.len() as u32).to_le_bytes()[..], payload, padding].concat()
+    /// }
+    ///
+    /// // An extended WebP with an odd-sized ICC profile before the EXIF chunk
+    /// let tiff = b"II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0\x06\0\0\0\0\0\0\0";
+    /// let chunks = [
+    ///     chunk(b"VP8X", b"\x28\0\0\0\0\0\0\0\0\0"),
+    ///     chunk(b"ICCP", b"icc"),
+    ///     chunk(b"VP8L", b"\x2f\0\0\0\0"),
+    ///     chunk(b"EXIF", tiff),
+    /// ]
+    /// .concat();
+    /// let size = (4 + chunks.len() as u32).to_le_bytes();
+    /// let webp = [&b"RIFF"[..], &size, b"WEBP", &chunks].concat();
+    /// assert_eq!(Data::from_webp_bytes(&webp).unwrap().orientation(), Some(6));
+    /// ```
+    pub fn from_webp_bytes(bytes: &[u8]) -> Result<Data, ExifError> {
+        DataReader::new().from_webp_bytes(bytes)
+    }
+
+    /// Construct a new EXIF data container from a bare TIFF block, starting with the `II*` or
+    /// `MM*` header, see [`raw_exif_block()`](#method.raw_exif_block).
+    pub fn from_raw_exif_block(tiff: &[u8]) -> Result<Data, ExifError> {
+        DataReader::new().from_raw_exif_block(tiff)
+    }
+
+    /// Construct a new EXIF data container with EXIF data from the contents of a JPEG file.
+    ///
+    /// The EXIF data is found even when other APP1 segments, e.g., XMP, come first.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let jpeg = std::fs::read("assets/file.jpg").unwrap();
+    ///
+    /// // Move the XMP segment in front of the EXIF segment
+    /// let (exif, xmp) = (&jpeg[2..272], &jpeg[272..2968]);
+    /// assert!(xmp[4..].starts_with(b"http://ns.adobe.com/xap/"));
+    /// let reordered = [&jpeg[..2], xmp, exif, &jpeg[2968..]].concat();
+    ///
+    /// let data = Data::from_bytes(&reordered).unwrap();
+    /// assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// ```
+    ///
+    /// Segments with a lowercase `exif\0\0` identifier, or with only a single NUL after
+    /// `Exif`, are read too when there's no segment with the canonical identifier:
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let jpeg = std::fs::read("assets/file.jpg").unwrap();
+    ///
+    /// let mut lowercase = jpeg.clone();
+    /// lowercase[6] = b'e';
+    /// let data = Data::from_bytes(&lowercase).unwrap();
+    /// assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// assert_eq!(data.tiff_header_offset(), Some(12));
+    ///
+    /// // Drop the second NUL and shorten the segment accordingly
+    /// let len = u16::from_be_bytes([jpeg[4], jpeg[5]]) - 1;
+    /// let short = [&jpeg[..4], &len.to_be_bytes(), &jpeg[6..11], &jpeg[12..]].concat();
+    /// let data = Data::from_bytes(&short).unwrap();
+    /// assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// assert_eq!(data.tiff_header_offset(), Some(11));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Data, ExifError> {
+        DataReader::new().from_bytes(bytes)
+    }
+
+    /// Replace the EXIF data with the EXIF data from the contents of a JPEG file, or from an
+    /// EXIF block starting with the `Exif\0\0` signature.
+    ///
+    /// The entries, the maker note and the thumbnail are dropped before the new data is parsed
+    /// with the same options, allocator and character set, so a single `Data` can be reused for
+    /// many files. Any `Entry` or `Content` borrowed from the data before the reload is
+    /// invalidated by it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Data;
+    /// let mut data = Data::new();
+    /// for path in ["assets/file.jpg"] {
+    ///     let bytes = std::fs::read(path).unwrap();
+    ///     data.load_into(&bytes).unwrap();
+    ///     println!("{}: {:?}", path, data.byte_order());
+    /// }
+    ///
+    /// // Little-endian TIFF data with an IFD1 pointing at a thumbnail
+    /// let mut tiff = Vec::new();
+    /// tiff.extend_from_slice(b"Exif\0\0II*\0\x08\0\0\0\0\0\x0e\0\0\0");
+    /// tiff.extend_from_slice(&[2, 0, 0x01, 0x02, 4, 0, 1, 0, 0, 0, 44, 0, 0, 0]);
+    /// tiff.extend_from_slice(&[0x02, 0x02, 4, 0, 1, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0]);
+    /// tiff.extend_from_slice(b"\xff\xd8\xff\xd9");
+    /// data.load_into(&tiff).unwrap();
+    /// assert!(data.thumbnail().is_some());
+    ///
+    /// // Nothing of the previous file is left, neither its thumbnail nor its maker note
+    /// data.load_into(b"Exif\0\0II*\0\x08\0\0\0\0\0\0\0\0\0").unwrap();
+    /// assert_eq!(data.thumbnail(), None);
+    /// assert!(data.maker_note().is_none());
+    /// ```
+    pub fn load_into(&mut self, bytes: &[u8]) -> Result<(), ExifError> {
+        let (exif, tiff_header_offset) = match jpeg::find_exif_segment(bytes) {
+            Scan::Found(segment) => (segment.normalized(bytes), Some(segment.tiff_header)),
+            Scan::NotJpeg if bytes.starts_with(jpeg::EXIF_SIGNATURE) => (bytes.into(), None),
+            Scan::Incomplete(segment) => return Err(jpeg::incomplete_error(bytes.len(), segment)),
+            _ => return Err(invalid_data()),
+        };
+
+        self.reset()?;
+        self.tiff_header_offset = tiff_header_offset;
+        self.load_data(&exif);
+
+        Ok(())
+    }
+
+    /// Parse an EXIF block starting with the `Exif\0\0` signature into the data, following
+    /// its options.
+    ///
+    /// libexif drops the tags it doesn't record in an IFD both while loading with
+    /// `IgnoreUnknownTags` and while fixing with `FollowSpecification`, which loses Windows'
+    /// XP tags. The data is loaded with both options disabled and they're applied afterwards,
+    /// keeping the XP tags, see [`xp_title()`](#method.xp_title).
+    pub(crate) fn load_data(&mut self, exif: &[u8]) {
+        for option in [
+            DataOption::IgnoreUnknownTags,
+            DataOption::FollowSpecification,
+        ] {
+            unsafe { exif_data_unset_option(self.inner, option.to_libexif()) };
+        }
+
+        unsafe { exif_data_load_data(self.inner, exif.as_ptr(), exif.len() as c_uint) };
+
+        if self.options.contains(&DataOption::IgnoreUnknownTags) {
+            self.remove_unknown_tags();
+        }
+        if self.options.contains(&DataOption::FollowSpecification) {
+            self.fix();
+        }
+        self.apply_options();
+    }
+
+    /// Remove the entries of the tags libexif doesn't record in their IFD, but the XP tags.
+    fn remove_unknown_tags(&mut self) {
+        for &content in self.inner.ifd.iter() {
+            if content.is_null() {
+                continue;
+            }
+
+            let ifd = unsafe { exif_content_get_ifd(content) };
+            let count = unsafe { (*content).count } as usize;
+            for index in (0..count).rev() {
+                let entry = unsafe { *(*content).entries.add(index) };
+                let tag = unsafe { (*entry).tag };
+                let known = !unsafe { exif_tag_get_name_in_ifd(tag, ifd) }.is_null();
+                let keep = known || (ifd == ExifIfd::EXIF_IFD_0 && xp::is_xp_tag(tag));
+                if !keep {
+                    unsafe { exif_content_remove_entry(content, entry) };
+                }
             }
+        }
+    }
+
+    /// Replace the `ExifData` with a new one with the same allocator, options and encoding.
+    ///
+    /// Removing the entries isn't enough: libexif only replaces the maker note and the
+    /// thumbnail of the old data when the new data has them.
+    fn reset(&mut self) -> Result<(), ExifError> {
+        let ptr = unsafe { exif_data_new_mem(self.mem.as_ptr()) };
+        if ptr.is_null() {
+            return Err(ExifError::ExifDataNull);
+        }
+
+        let encoding = self.encoding();
+        let old = std::mem::replace(&mut self.inner, unsafe { &mut *ptr });
+        unsafe { exif_data_unref(old) };
+
+        self.set_encoding(encoding);
+        self.apply_options();
+        Ok(())
+    }
 
-            if !loader.write_data(&mut buffer) {
-                break;
+    /// Set the options of the underlying `ExifData` to the ones recorded in `options`.
+    fn apply_options(&mut self) {
+        for option in [
+            DataOption::IgnoreUnknownTags,
+            DataOption::FollowSpecification,
+            DataOption::DontChangeMakerNote,
+        ] {
+            if self.options.contains(&option) {
+                unsafe { exif_data_set_option(self.inner, option.to_libexif()) };
+            } else {
+                unsafe { exif_data_unset_option(self.inner, option.to_libexif()) };
             }
         }
+    }
 
-        loader
-            .data()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid EXIF data").into())
+    /// Pointer to the underlying `ExifData`, for the libexif calls that take a mutable pointer
+    /// without modifying the data.
+    pub(crate) fn as_ptr(&self) -> *mut ExifData {
+        self.inner as *const _ as *mut _
     }
 
     /// Return the byte order in use by this EXIF data.
@@ -90,10 +582,27 @@ impl Data {
     }
 
     /// Set the byte order used for this EXIF data.
-    pub fn set_byte_order(&mut self, byte_order: ByteOrder) {
+    ///
+    /// libexif converts the entries that are already present, and the maker note, to the new
+    /// byte order, so their values stay the same.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Data, ExifTag, IFD, Value};
+    /// let mut data = Data::new();
+    /// data.set_byte_order(ByteOrder::BigEndian);
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, 6u16.into(), ByteOrder::BigEndian)
+    ///     .unwrap();
+    ///
+    /// data.set_byte_order(ByteOrder::LittleEndian);
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION).unwrap();
+    /// assert_eq!(entry.value_auto().unwrap(), Value::U16(vec![6]));
+    /// ```
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) -> &mut Self {
         unsafe {
             exif_data_set_byte_order(self.inner, byte_order.to_libexif());
         }
+        self
     }
 
     /// Return the encoding in use by this EXIF data.
@@ -104,24 +613,42 @@ impl Data {
     }
 
     /// Set the encoding used for this EXIF data.
-    pub fn set_encoding(&mut self, encoding: DataEncoding) {
+    ///
+    /// Like the other setters of the data's settings it returns the data, so they can be
+    /// chained.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Data, DataEncoding, DataOption};
+    /// let mut data = Data::new();
+    /// data.set_byte_order(ByteOrder::LittleEndian)
+    ///     .set_encoding(DataEncoding::Chunky)
+    ///     .unset_option(DataOption::FollowSpecification);
+    /// assert_eq!(data.byte_order(), ByteOrder::LittleEndian);
+    /// ```
+    pub fn set_encoding(&mut self, encoding: DataEncoding) -> &mut Self {
         unsafe {
             exif_data_set_data_type(self.inner, encoding.to_libexif());
         }
+        self
     }
 
     /// Enable a data processing option.
-    pub fn set_option(&mut self, option: DataOption) {
+    pub fn set_option(&mut self, option: DataOption) -> &mut Self {
         unsafe {
             exif_data_set_option(self.inner, option.to_libexif());
         }
+        self.options.insert(option);
+        self
     }
 
     /// Disable a data processing option.
-    pub fn unset_option(&mut self, option: DataOption) {
+    pub fn unset_option(&mut self, option: DataOption) -> &mut Self {
         unsafe {
             exif_data_unset_option(self.inner, option.to_libexif());
         }
+        self.options.remove(&option);
+        self
     }
 
     /// Get a Entry
@@ -135,21 +662,22 @@ impl Data {
     ///         .unwrap()
     ///         .value(ByteOrder::LittleEndian);
     /// ```
+    ///
+    /// The tag can also be given as a [`Tag`](struct.Tag.html):
+    /// ```rust
+    /// use libexif::{Data, ExifTag, Tag, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let tag = Tag::from(ExifTag::EXIF_TAG_X_RESOLUTION);
+    /// assert_eq!(data.get_entry(IFD::Image, tag).unwrap().tag(), tag);
+    /// ```
     pub fn get_entry(
         &self,
         ifd: impl ToLibExif<ExifIfd>,
-        tag: ExifTag,
+        tag: impl Into<ExifTag>,
     ) -> Result<Entry, ExifError> {
-        // The C call to this function
-        // exif_content_get_entry(exif->ifd[ifd], tag)
-        let entry_ptr =
-            unsafe { exif_content_get_entry(self.inner.ifd[ifd.to_libexif() as usize], tag) };
+        let entry = self.get_entry_raw(ifd, tag)?;
 
-        if entry_ptr.is_null() {
-            Err(ExifError::EntryNotFound)
-        } else {
-            Ok(Entry::from_libexif(unsafe { &mut *entry_ptr }))
-        }
+        Ok(Entry::from_libexif(entry).with_charset(self.charset))
     }
 
     /// Returns a raw entry directly from the C bindings without any wrapper
@@ -159,10 +687,17 @@ impl Data {
     pub fn get_entry_raw(
         &self,
         ifd: impl ToLibExif<ExifIfd>,
-        tag: ExifTag,
+        tag: impl Into<ExifTag>,
     ) -> Result<&mut ExifEntry, ExifError> {
-        let entry_ptr =
-            unsafe { exif_content_get_entry(self.inner.ifd[ifd.to_libexif() as usize], tag) };
+        // The C call to this function
+        // exif_content_get_entry(exif->ifd[ifd], tag)
+        let ifd = ifd.to_libexif();
+        if ifd == ExifIfd::EXIF_IFD_COUNT {
+            return Err(ExifError::InvalidIfd(IFD::Count));
+        }
+
+        let content = self.content_ptr(ifd).ok_or(ExifError::EntryNotFound)?;
+        let entry_ptr = unsafe { exif_content_get_entry(content, tag.into()) };
         if entry_ptr.is_null() {
             Err(ExifError::EntryNotFound)
         } else {
@@ -170,6 +705,80 @@ impl Data {
         }
     }
 
+    /// Return the value of an entry, or the value recorded for it with
+    /// [`set_override()`](#method.set_override).
+    ///
+    /// The value of the entry is decoded with
+    /// [`Entry::value_auto()`](struct.Entry.html#method.value_auto).
+    pub fn get_value(&self, ifd: IFD, tag: impl Into<ExifTag>) -> Result<Value, ExifError> {
+        let tag = tag.into();
+        if let Some(value) = self.overrides.get(&(ifd, Tag::from(tag))) {
+            return Ok(value.clone());
+        }
+
+        self.get_entry(ifd, tag)?.value_auto()
+    }
+
+    /// Record a value to return from [`get_value()`](#method.get_value) instead of the entry's,
+    /// without changing the EXIF data yet.
+    ///
+    /// This previews an edit, e.g., an orientation from a sidecar file. The overrides are only
+    /// set in the EXIF data when it is serialized, by the `write` methods or
+    /// [`raw_exif_block()`](#method.raw_exif_block), which fail like
+    /// [`set_entry()`](#method.set_entry) when an override can't be set.
+    /// [`clone_deep()`](#method.clone_deep) copies them as they are.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    /// data.set_override(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![6]));
+    ///
+    /// let orientation = data.get_value(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION).unwrap();
+    /// assert_eq!(orientation, Value::U16(vec![6]));
+    ///
+    /// data.clear_overrides();
+    /// let original = data.get_value(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION).ok();
+    /// assert_ne!(original, Some(orientation));
+    /// ```
+    ///
+    /// Setting the entry afterwards replaces the override:
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    /// let order = data.byte_order();
+    /// let tag = ExifTag::EXIF_TAG_ORIENTATION;
+    /// data.set_override(IFD::Image, tag, Value::U16(vec![6]));
+    /// data.set_entry(IFD::Image, tag, Value::U16(vec![3]), order).unwrap();
+    ///
+    /// let tiff = data.raw_exif_block().unwrap();
+    /// let copy = Data::from_raw_exif_block(&tiff).unwrap();
+    /// assert_eq!(copy.get_value(IFD::Image, tag).unwrap(), Value::U16(vec![3]));
+    /// ```
+    pub fn set_override(&mut self, ifd: IFD, tag: impl Into<ExifTag>, value: Value) {
+        self.overrides.insert((ifd, Tag::from(tag.into())), value);
+    }
+
+    /// Drop the values recorded with [`set_override()`](#method.set_override) that haven't
+    /// been set in the EXIF data yet.
+    pub fn clear_overrides(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// Set the recorded overrides in the EXIF data, the ones that fail are kept.
+    fn apply_overrides(&mut self) -> Result<(), ExifError> {
+        let order = self.byte_order();
+        let keys: Vec<_> = self.overrides.keys().copied().collect();
+
+        for (ifd, tag) in keys {
+            let value = self.overrides[&(ifd, tag)].clone();
+            self.set_entry(ifd, tag, value, order)?;
+            self.overrides.remove(&(ifd, tag));
+        }
+
+        Ok(())
+    }
+
     /// Set an entry
     ///
     /// Example
@@ -184,54 +793,166 @@ impl Data {
     /// );
     /// ```
     /// Need to know IFD, Tag, Value and ByteOrder Before Hand
+    ///
+    /// The thumbnail directory (IFD1) is a regular TIFF directory, so besides the thumbnail
+    /// specific tags it accepts every tag libexif knows in the image directory (IFD0):
+    /// ```rust
+    /// use libexif::{IFD, ExifTag, Data, Value};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Thumbnail, ExifTag::EXIF_TAG_COMPRESSION, Value::U16(vec![6]), order)
+    ///     .unwrap();
+    /// data.set_entry(
+    ///     IFD::Thumbnail,
+    ///     ExifTag::EXIF_TAG_JPEG_INTERCHANGE_FORMAT,
+    ///     Value::U32(vec![0]),
+    ///     order,
+    /// )
+    /// .unwrap();
+    /// data.set_entry(
+    ///     IFD::Thumbnail,
+    ///     ExifTag::EXIF_TAG_JPEG_INTERCHANGE_FORMAT_LENGTH,
+    ///     Value::U32(vec![0]),
+    ///     order,
+    /// )
+    /// .unwrap();
+    ///
+    /// let compression = data
+    ///     .get_entry(IFD::Thumbnail, ExifTag::EXIF_TAG_COMPRESSION)
+    ///     .unwrap()
+    ///     .value(order);
+    /// assert!(matches!(compression, Value::U16(v) if v == [6]));
+    /// ```
+    ///
+    /// An existing entry takes the number of components of the new value, e.g., when a scalar
+    /// becomes a triple:
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// let tag = ExifTag::EXIF_TAG_BITS_PER_SAMPLE;
+    /// data.set_entry(IFD::Image, tag, Value::U16(vec![8]), order).unwrap();
+    /// data.set_entry(IFD::Image, tag, Value::U16(vec![8, 8, 8]), order).unwrap();
+    ///
+    /// let entry = data.get_entry(IFD::Image, tag).unwrap();
+    /// assert_eq!(entry.components(), 3);
+    /// assert_eq!(entry.raw_data().len(), 6);
+    /// assert_eq!(entry.value(order), Value::U16(vec![8, 8, 8]));
+    /// ```
+    ///
+    /// When the value can't be stored, e.g., text with a NUL in it, the data is left unchanged:
+    /// ```rust
+    /// use libexif::{Data, ExifError, ExifTag, IFD, Value};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// let text = Value::Text("Jane\0Doe".to_string());
+    ///
+    /// let result = data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, text, order);
+    /// assert!(matches!(result, Err(ExifError::NulError(_))));
+    /// assert!(matches!(
+    ///     data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST),
+    ///     Err(ExifError::EntryNotFound)
+    /// ));
+    /// ```
+    ///
+    /// The value has to be encoded in the byte order of the data, see
+    /// [`byte_order()`](#method.byte_order), otherwise it would read back as garbage:
+    /// ```rust
+    /// use libexif::{ByteOrder, Data, ExifError, ExifTag, IFD};
+    /// let mut data = Data::new();
+    /// data.set_byte_order(ByteOrder::BigEndian);
+    /// let tag = ExifTag::EXIF_TAG_ORIENTATION;
+    /// let result = data.set_entry(IFD::Image, tag, 6u16.into(), ByteOrder::LittleEndian);
+    /// assert!(matches!(result, Err(ExifError::ByteOrderMismatch { .. })));
+    /// ```
+    ///
+    /// Text that doesn't fit in a tag the EXIF specification gives a fixed length is rejected
+    /// too, text tags without one such as `Artist` take any length:
+    /// ```rust
+    /// use libexif::{Data, ExifError, ExifTag, IFD};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// let tag = ExifTag::EXIF_TAG_DATE_TIME;
+    /// let result = data.set_entry(IFD::Image, tag, "yesterday at noon".into(), order);
+    /// assert!(result.is_ok());
+    /// let result = data.set_entry(IFD::Image, tag, "2021:07:14 18:30:05 UTC".into(), order);
+    /// assert!(matches!(result, Err(ExifError::TextTooLong { max: 20, found: 24 })));
+    ///
+    /// let artist = "Jane Doe, with help from the whole photo club";
+    /// let result = data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, artist.into(), order);
+    /// assert!(result.is_ok());
+    /// ```
+    ///
+    /// `IFD::Count` isn't an actual IFD and is rejected:
+    /// ```rust
+    /// use libexif::{Data, ExifError, ExifTag, IFD, Value};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// let result = data.set_entry(IFD::Count, ExifTag::EXIF_TAG_ARTIST, "".into(), order);
+    /// assert!(matches!(result, Err(ExifError::InvalidIfd(IFD::Count))));
+    /// assert!(matches!(
+    ///     data.get_entry(IFD::Count, ExifTag::EXIF_TAG_ARTIST),
+    ///     Err(ExifError::InvalidIfd(IFD::Count))
+    /// ));
+    /// ```
     pub fn set_entry(
         &mut self,
         ifd: IFD,
-        // tag: impl ToLibExif<ExifTag>,
-        tag: ExifTag,
+        tag: impl Into<ExifTag>,
         value: Value,
         order: ByteOrder,
     ) -> Result<(), ExifError> {
-        // First calculate the components, size, and format of the value
-        let (components, size, format) = value.get_components_size_format()?;
-        let tag_name_ptr = unsafe { exif_tag_get_title_in_ifd(tag, ifd.to_libexif()) };
+        let tag = tag.into();
+
+        if ifd == IFD::Count {
+            return Err(ExifError::InvalidIfd(ifd));
+        }
 
         // Check if the tag is unknown
-        if tag_name_ptr.is_null() {
+        if !tag_known_in_ifd(tag, ifd) {
             return Err(ExifError::TagNotInIfd(tag, ifd));
         }
 
-        // First check if the entry exists
-        if let Ok(entry) = self.get_entry_raw(ifd, tag) {
-            // Check if the format matches the entry
-            if entry.format != format {
-                return Err(ExifError::FormatMismatch(entry.format, format));
+        if let Value::Text(text) = &value {
+            let found = text.len() + 1;
+            match Tag::from(tag).fixed_text_len(ifd) {
+                Some(max) if found > max => return Err(ExifError::TextTooLong { max, found }),
+                _ => {}
             }
+        }
 
-            // Check if the memory need reallocation
-            if entry.size != (components * size) as u32 {
-                let mem = unsafe { exif_mem_new_default() };
-                if mem.is_null() {
-                    return Err(ExifError::MemNewFail);
-                }
+        self.set_entry_unchecked(ifd, tag, value, order)
+    }
 
-                unsafe {
-                    entry.size = (components * size) as u32;
-                    entry.components = components as u64;
-                    exif_mem_realloc(
-                        mem,
-                        entry.data as *mut libc::c_void,
-                        (components * size) as u32,
-                    );
-                    // exif_content_add_entry(self.to_libexif().ifd[ifd.to_libexif() as usize], entry);
-                    exif_mem_unref(mem);
-                };
-            }
+    /// Same as [`set_entry`](#method.set_entry) but doesn't check whether libexif knows the tag
+    /// in the given IFD, used for vendor extensions that aren't in libexif's tag table.
+    pub(crate) fn set_entry_unchecked(
+        &mut self,
+        ifd: IFD,
+        tag: ExifTag,
+        value: Value,
+        order: ByteOrder,
+    ) -> Result<(), ExifError> {
+        if ifd == IFD::Count {
+            return Err(ExifError::InvalidIfd(ifd));
+        }
+
+        let expected = self.byte_order();
+        if order != expected {
+            return Err(ExifError::ByteOrderMismatch {
+                expected,
+                found: order,
+            });
+        }
 
-            value.insert(*entry, components, order)?;
+        // First check if the entry exists
+        if let Ok(entry) = self.get_entry_raw(ifd, tag) {
+            crate::entry::overwrite_value(entry, value, order, Some(&self.mem))?;
         } else {
+            let (components, size, format) = value.get_components_size_format()?;
             let entry = crate::tag::create_tag(
                 self.to_libexif(),
+                &self.mem,
                 ifd,
                 tag,
                 components as u64,
@@ -239,30 +960,399 @@ impl Data {
                 format,
             )?;
 
-            value.insert(entry, components, order)?;
+            if let Err(err) = value.insert(entry, components, order) {
+                // Don't leave the new entry behind with whatever libexif initialized it to
+                if let Ok(entry) = self.get_entry_raw(ifd, tag) {
+                    unsafe { exif_content_remove_entry(entry.parent, entry) };
+                }
+
+                return Err(err);
+            }
         }
 
+        // The value that was just set replaces any pending override
+        self.overrides.remove(&(ifd, Tag::from(tag)));
+
         Ok(())
     }
 
-    /// Iterate over the contents of the EXIF data.
+    /// Iterate over the contents of the EXIF data, one for each IFD but `IFD::Count`.
     pub fn contents(&self) -> impl ExactSizeIterator<Item = Content> {
         Contents {
-            contents: &self.inner.ifd[..],
+            contents: &self.inner.ifd[..ExifIfd::EXIF_IFD_COUNT as usize],
             index: 0,
+            charset: self.charset,
+            mem: &self.mem,
         }
     }
 
+    /// Return the contents of a single IFD, or `None` for `IFD::Count`.
+    ///
+    /// `Data` can't implement `Index<IFD>` because the `Content` wrapper is created on demand and
+    /// `Index` has to return a reference, so this is the way to access a specific IFD.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let image = data.content(IFD::Image).unwrap();
+    /// for entry in image.entries() {
+    ///     println!("{}", entry.tag().title(IFD::Image));
+    /// }
+    /// ```
+    pub fn content(&self, ifd: IFD) -> Option<Content> {
+        let content = self.content_ptr(ifd.to_libexif())?;
+
+        Some(
+            Content::from_libexif(unsafe { &mut *content })
+                .with_charset(self.charset)
+                .with_allocator(&self.mem),
+        )
+    }
+
+    /// Return the IFDs that have at least one entry, e.g., to check if there's GPS data.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let ifds = data.present_ifds();
+    /// assert!(ifds.contains(&IFD::Image));
+    /// assert!(!ifds.contains(&IFD::GPS));
+    /// ```
+    pub fn present_ifds(&self) -> Vec<IFD> {
+        self.contents()
+            .filter(|content| !content.is_empty())
+            .map(|content| content.ifd())
+            .collect()
+    }
+
+    /// The `ExifContent` of an IFD, or `None` for `EXIF_IFD_COUNT`.
+    fn content_ptr(&self, ifd: ExifIfd) -> Option<*mut ExifContent> {
+        self.inner
+            .ifd
+            .get(ifd as usize)
+            .copied()
+            .filter(|content| !content.is_null())
+    }
+
+    /// List the titles of the tags in every IFD without decoding any values.
+    ///
+    /// Tags libexif doesn't know are left out.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// for (ifd, title) in data.tag_names() {
+    ///     println!("{:?}: {}", ifd, title);
+    /// }
+    /// ```
+    pub fn tag_names(&self) -> Vec<(IFD, &'static str)> {
+        self.contents()
+            .flat_map(|content| {
+                let ifd = content.ifd();
+                content
+                    .entries()
+                    .filter(|entry| entry.is_known_tag())
+                    .map(|entry| (ifd, entry.tag().title(ifd)))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Compare the tags of two EXIF data by their decoded values.
+    ///
+    /// Both have to contain the same tags in the same IFDs with equal values. The order of the
+    /// entries and the byte order they are stored in don't matter. Values recorded with
+    /// [`set_override()`](#method.set_override) are compared like
+    /// [`get_value()`](#method.get_value) returns them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Data};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    /// let mut copy = data.clone_deep().unwrap();
+    /// copy.set_byte_order(ByteOrder::LittleEndian);
+    /// assert!(data.exif_eq(&copy));
+    /// ```
+    pub fn exif_eq(&self, other: &Data) -> bool {
+        self.entry_values() == other.entry_values()
+    }
+
+    /// The decoded value of every entry, entries that can't be decoded are kept as their raw
+    /// bytes. The pending overrides take the place of the entries' values.
+    pub(crate) fn entry_values(&self) -> HashMap<(IFD, Tag), Value> {
+        let mut values: HashMap<_, _> = self
+            .contents()
+            .flat_map(|content| {
+                let ifd = content.ifd();
+                content
+                    .entries()
+                    .map(|entry| {
+                        let value = entry
+                            .value_auto()
+                            .unwrap_or_else(|_| Value::Undefined(entry.raw_data().to_vec()));
+                        ((ifd, entry.tag()), value)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        values.extend(self.overrides.clone());
+        values
+    }
+
     /// Return the raw binary data for the ExifData
     pub fn raw_data(&self) -> &[u8] {
-        unsafe { slice::from_raw_parts(self.inner.data, self.inner.size as usize) }
+        self.thumbnail().unwrap_or(&[])
+    }
+
+    /// Returns the thumbnail embedded in the EXIF data, usually a JPEG, without copying it.
+    ///
+    /// The bytes are borrowed from the data, so they can be written out as they are, e.g., as
+    /// the body of a preview response, see the `serve_thumbnail` example.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// if let Some(thumbnail) = data.thumbnail() {
+    ///     assert!(thumbnail.starts_with(&[0xff, 0xd8]));
+    /// }
+    /// ```
+    pub fn thumbnail(&self) -> Option<&[u8]> {
+        if self.inner.data.is_null() || self.inner.size == 0 {
+            return None;
+        }
+
+        Some(unsafe { slice::from_raw_parts(self.inner.data, self.inner.size as usize) })
+    }
+
+    /// Returns every embedded preview the crate can find: the thumbnail, followed by the JPEG
+    /// images stored in entries such as the maker note.
+    ///
+    /// Previews in entries are found by looking for the JPEG start and end markers, previews
+    /// that are stored outside of the EXIF data and only referenced by an offset can't be
+    /// found.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// let largest = data.previews().into_iter().max_by_key(|preview| preview.len());
+    /// if let Some(preview) = largest {
+    ///     assert!(preview.starts_with(&[0xff, 0xd8]));
+    /// }
+    /// ```
+    pub fn previews(&self) -> Vec<&[u8]> {
+        let mut previews = Vec::from_iter(self.thumbnail());
+
+        for &content in self.inner.ifd.iter().filter(|content| !content.is_null()) {
+            let content = unsafe { &*content };
+            if content.entries.is_null() {
+                continue;
+            }
+
+            let entries = unsafe { slice::from_raw_parts(content.entries, content.count as usize) };
+            for &entry in entries {
+                let entry = unsafe { &*entry };
+                if entry.data.is_null() {
+                    continue;
+                }
+
+                let data = unsafe { slice::from_raw_parts(entry.data, entry.size as usize) };
+                previews.extend(jpeg::embedded_jpegs(data));
+            }
+        }
+
+        previews
+    }
+
+    /// Make the thumbnail IFD describe the embedded thumbnail, see
+    /// [`thumbnail()`](#method.thumbnail).
+    ///
+    /// `Compression` is set to 6 (JPEG). libexif writes `JPEGInterchangeFormat` and
+    /// `JPEGInterchangeFormatLength` itself when the data is saved, so entries for them, e.g.,
+    /// set with [`set_entry()`](#method.set_entry), are removed instead of being written as a
+    /// second, stale pair. Nothing is changed when there's no thumbnail.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// // Little-endian TIFF data: IFD0 with an orientation, then IFD1 pointing at a thumbnail
+    /// let mut tiff = Vec::new();
+    /// tiff.extend_from_slice(b"II*\0\x08\0\0\0");
+    /// tiff.extend_from_slice(&[1, 0, 0x12, 0x01, 3, 0, 1, 0, 0, 0, 1, 0, 0, 0, 26, 0, 0, 0]);
+    /// tiff.extend_from_slice(&[2, 0, 0x01, 0x02, 4, 0, 1, 0, 0, 0, 56, 0, 0, 0]);
+    /// tiff.extend_from_slice(&[0x02, 0x02, 4, 0, 1, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0]);
+    /// tiff.extend_from_slice(b"\xff\xd8\xff\xd9");
+    ///
+    /// let mut data = Data::from_raw_exif_block(&tiff).unwrap();
+    /// let thumbnail = data.thumbnail().unwrap().to_vec();
+    /// assert_eq!(thumbnail, b"\xff\xd8\xff\xd9");
+    ///
+    /// // A stale offset, e.g., copied from another file
+    /// let order = data.byte_order();
+    /// let tag = ExifTag::EXIF_TAG_JPEG_INTERCHANGE_FORMAT;
+    /// data.set_entry(IFD::Thumbnail, tag, Value::U32(vec![0]), order).unwrap();
+    ///
+    /// data.fix_thumbnail_tags().unwrap();
+    /// let path = std::env::temp_dir().join("libexif-fix-thumbnail-tags.jpg");
+    /// data.write_from_file("assets/file.jpg", &path).unwrap();
+    /// assert_eq!(Data::open(&path).unwrap().thumbnail(), Some(&thumbnail[..]));
+    ///
+    /// // Read the tags of IFD1 from the written TIFF data
+    /// let written = std::fs::read(&path).unwrap();
+    /// let segment = libexif::find_app1_exif(&written).unwrap();
+    /// let tiff = &written[segment.start + 10..segment.end];
+    /// let u16_at = |pos: usize| u16::from_le_bytes([tiff[pos], tiff[pos + 1]]) as usize;
+    /// let u32_at = |pos: usize| u32::from_le_bytes(tiff[pos..pos + 4].try_into().unwrap());
+    /// let ifd0 = u32_at(4) as usize;
+    /// let ifd1 = u32_at(ifd0 + 2 + 12 * u16_at(ifd0)) as usize;
+    /// let values = |tag: usize| {
+    ///     (0..u16_at(ifd1))
+    ///         .map(|index| ifd1 + 2 + 12 * index)
+    ///         .filter(|&entry| u16_at(entry) == tag)
+    ///         .map(|entry| u32_at(entry + 8) as usize)
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// let (offsets, lengths) = (values(0x0201), values(0x0202));
+    /// assert_eq!((offsets.len(), lengths.len()), (1, 1));
+    /// assert_eq!(&tiff[offsets[0]..offsets[0] + lengths[0]], &thumbnail[..]);
+    /// assert_eq!(values(0x0103), [6]);
+    /// ```
+    pub fn fix_thumbnail_tags(&mut self) -> Result<(), ExifError> {
+        if self.thumbnail().is_none() {
+            return Ok(());
+        }
+
+        for tag in [
+            ExifTag::EXIF_TAG_JPEG_INTERCHANGE_FORMAT,
+            ExifTag::EXIF_TAG_JPEG_INTERCHANGE_FORMAT_LENGTH,
+        ] {
+            if let Ok(entry) = self.get_entry_raw(IFD::Thumbnail, tag) {
+                unsafe { exif_content_remove_entry(entry.parent, entry) };
+            }
+        }
+
+        let order = self.byte_order();
+        self.set_entry(
+            IFD::Thumbnail,
+            ExifTag::EXIF_TAG_COMPRESSION,
+            Value::U16(vec![6]),
+            order,
+        )
     }
 
     /// Fix the EXIF data to make it compatible with the EXIF specification.
+    ///
+    /// libexif removes the tags it doesn't record in an IFD, the XP tags of the image IFD are
+    /// kept anyway, see [`xp_title()`](#method.xp_title).
     pub fn fix(&mut self) {
+        let xp: Vec<*mut ExifEntry> = xp::XP_TAGS
+            .iter()
+            .filter_map(|&tag| self.get_entry_raw(IFD::Image, tag).ok())
+            .map(|entry| entry as *mut ExifEntry)
+            .collect();
+
+        // Hold on to the XP entries while they're out of the IFD
+        for &entry in &xp {
+            unsafe {
+                exif_entry_ref(entry);
+                exif_content_remove_entry((*entry).parent, entry);
+            }
+        }
+
         unsafe {
             exif_data_fix(self.inner);
         }
+
+        if let Some(content) = self.content_ptr(ExifIfd::EXIF_IFD_0) {
+            for &entry in &xp {
+                unsafe { exif_content_add_entry(content, entry) };
+            }
+        }
+        for &entry in &xp {
+            unsafe { exif_entry_unref(entry) };
+        }
+    }
+
+    /// Add default entries for the mandatory tags missing from the given IFD.
+    ///
+    /// Every tag that is `Required` in the IFD for the data's encoding and isn't present yet is
+    /// created with libexif's default value. Unlike [`fix()`](#method.fix) existing entries and
+    /// the maker note are left untouched.
+    pub fn add_missing_mandatory(&mut self, ifd: IFD) -> Result<(), ExifError> {
+        let content = match self.content_ptr(ifd.to_libexif()) {
+            Some(content) => content,
+            None => return Ok(()),
+        };
+
+        let encoding = self.encoding();
+
+        for tag in crate::tag::known_tags() {
+            let support_level = SupportLevel::from_libexif(unsafe {
+                exif_tag_get_support_level_in_ifd(tag, ifd.to_libexif(), encoding.to_libexif())
+            });
+
+            if !support_level.is_required()
+                || !unsafe { exif_content_get_entry(content, tag) }.is_null()
+            {
+                continue;
+            }
+
+            // exif_entry_initialize allocates the value with the entry's allocator
+            let entry = unsafe { exif_entry_new_mem(self.mem.as_ptr()) };
+            if entry.is_null() {
+                return Err(ExifError::EntryNewFail);
+            }
+
+            // Same as exif_content_fix, the entry has to be attached before it can be initialized
+            unsafe {
+                exif_content_add_entry(content, entry);
+                exif_entry_initialize(entry, tag);
+                exif_entry_unref(entry);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List every tag the EXIF specification records in the given IFD, with its support level
+    /// for the given encoding and whether the data has it.
+    ///
+    /// Tags are listed in the order of libexif's tag table. Checking that no `Required` tag is
+    /// missing tells whether the IFD is complete.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let report = data.completeness_report(IFD::Image, data.encoding());
+    /// for (tag, level, present) in report {
+    ///     if level.is_required() && !present {
+    ///         println!("missing {}", tag.name(IFD::Image));
+    ///     }
+    /// }
+    /// ```
+    pub fn completeness_report(
+        &self,
+        ifd: IFD,
+        encoding: DataEncoding,
+    ) -> Vec<(Tag, SupportLevel, bool)> {
+        let content = self.content_ptr(ifd.to_libexif());
+
+        crate::tag::known_tags()
+            .into_iter()
+            .filter(|&tag| !unsafe { exif_tag_get_name_in_ifd(tag, ifd.to_libexif()) }.is_null())
+            .map(|tag| {
+                let present = content.is_some_and(|content| {
+                    !unsafe { exif_content_get_entry(content, tag) }.is_null()
+                });
+                let tag = Tag::from_libexif(tag);
+
+                (tag, tag.support_level(ifd, encoding), present)
+            })
+            .collect()
     }
 
     /// Dump all EXIF data to stdout.
@@ -281,65 +1371,504 @@ impl Data {
         self.write(old_buffer, to)
     }
 
+    /// Same as [`write_from_file()`](#method.write_from_file) but doesn't fix the EXIF data
+    /// first, see [`write_preserving()`](#method.write_preserving).
+    pub fn write_from_file_preserving(
+        &mut self,
+        from: impl AsRef<Path>,
+        to: impl AsRef<Path>,
+    ) -> Result<(), ExifError> {
+        let old_buffer = std::fs::read(from)?;
+        self.write_preserving(old_buffer, to)
+    }
+
+    /// Same as [`write()`](#method.write) with the file the data was opened from, which must
+    /// have been kept with [`open_retaining()`](#method.open_retaining).
+    ///
+    /// Fails with `NoSourceBuffer` when the data was opened any other way.
+    pub fn write_back(&mut self, to: impl AsRef<Path>) -> Result<(), ExifError> {
+        let source = self.source.take().ok_or(ExifError::NoSourceBuffer)?;
+        let result = self.write(&source, to);
+        self.source = Some(source);
+
+        result
+    }
+
+    /// Fix the EXIF data and write it into the JPEG `old_buffer`, saving the result to `to`.
+    ///
+    /// The EXIF segment of `old_buffer` is replaced where it is, e.g., after an ICC profile or
+    /// an Adobe segment, and the other segments are kept in their order. When there's none, the
+    /// new one is added right after the SOI marker, or after the JFIF APP0 segment that has to
+    /// come first.
+    ///
+    /// # Example
+    /// ```rust
+    /// let original = std::fs::read("assets/file.jpg").unwrap();
+    ///
+    /// // An APP2 segment with an ICC profile before the EXIF segment
+    /// let icc = [&[0xff, 0xe2, 0x00, 0x10][..], b"ICC_PROFILE\0\x01\x01"].concat();
+    /// let jpeg = [&original[..2], &icc, &original[2..]].concat();
+    ///
+    /// let mut data = libexif::Data::from_bytes(&jpeg).unwrap();
+    /// data.write(&jpeg, "/tmp/icc_first.jpg").unwrap();
+    ///
+    /// let written = std::fs::read("/tmp/icc_first.jpg").unwrap();
+    /// let exif = libexif::find_app1_exif(&written).unwrap();
+    /// assert_eq!(&written[2..exif.start], &icc[..]);
+    ///
+    /// // A JFIF file without EXIF data keeps its APP0 segment first
+    /// let jfif = [&[0xff, 0xe0, 0x00, 0x10][..], b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0"].concat();
+    /// let segment = libexif::find_app1_exif(&original).unwrap();
+    /// let jpeg = [&original[..2], &jfif, &original[segment.end..]].concat();
+    ///
+    /// data.write(&jpeg, "/tmp/jfif.jpg").unwrap();
+    ///
+    /// let written = std::fs::read("/tmp/jfif.jpg").unwrap();
+    /// let exif = libexif::find_app1_exif(&written).unwrap();
+    /// assert_eq!(&written[2..exif.start], &jfif[..]);
+    /// assert_eq!(&written[exif.end..], &original[segment.end..]);
+    /// ```
     pub fn write(
         &mut self,
         old_buffer: impl AsRef<[u8]>,
         to: impl AsRef<Path>,
     ) -> Result<(), ExifError> {
-        let mut exif_data: *mut u8 = std::ptr::null_mut();
-        let mut exif_data_len: u32 = 0;
-        unsafe {
-            exif_data_fix(self.inner);
-            exif_data_save_data(self.inner, &mut exif_data, &mut exif_data_len);
-        }
-        if exif_data.is_null() {
-            return Err(ExifError::ExifDataNull);
-        }
-        if exif_data_len == 0 {
-            return Err(ExifError::ExifDataLenZero);
-        }
-        let exif_data: &[u8] =
-            unsafe { std::slice::from_raw_parts_mut(exif_data, exif_data_len as usize) };
+        self.fix();
+        self.write_preserving(old_buffer, to)
+    }
+
+    /// Same as [`write()`](#method.write) but leaves the thumbnail out of the new file, which
+    /// is often the largest part of the EXIF data.
+    ///
+    /// The thumbnail is only detached while the data is saved, it's still there afterwards. The
+    /// other entries of the thumbnail IFD are written as they are.
+    ///
+    /// # Example
+    /// ```rust
+    /// let mut data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// let thumbnail = data.thumbnail().map(<[u8]>::to_vec);
+    /// let old_buffer = std::fs::read("assets/file.jpg").unwrap();
+    /// data.write_without_thumbnail(old_buffer, "/tmp/no_thumbnail.jpg").unwrap();
+    ///
+    /// let written = libexif::Data::open("/tmp/no_thumbnail.jpg").unwrap();
+    /// assert_eq!(written.thumbnail(), None);
+    /// assert_eq!(data.thumbnail().map(<[u8]>::to_vec), thumbnail);
+    /// ```
+    pub fn write_without_thumbnail(
+        &mut self,
+        old_buffer: impl AsRef<[u8]>,
+        to: impl AsRef<Path>,
+    ) -> Result<(), ExifError> {
+        let thumbnail = (self.inner.data, self.inner.size);
+        self.inner.data = std::ptr::null_mut();
+        self.inner.size = 0;
+
+        let result = self.write(old_buffer, to);
+        (self.inner.data, self.inner.size) = thumbnail;
+
+        result
+    }
+
+    /// Same as [`write()`](#method.write) but doesn't fix the EXIF data first.
+    ///
+    /// The entries and the byte order are written as they are, so reading a file, changing one
+    /// tag and writing it back only changes that tag. Open the file with `FollowSpecification`
+    /// disabled, otherwise libexif already fixes the data while loading it.
+    ///
+    /// Like `write()`, the structure of the new JPEG is checked before anything is written and
+    /// `ExifError::MalformedOutput` is returned when it is inconsistent, e.g., when the EXIF data
+    /// doesn't fit in a single APP1 segment.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataOption, DataReader, ExifTag, IFD, Value};
+    /// let mut data = DataReader::new()
+    ///     .without_option(DataOption::FollowSpecification)
+    ///     .open("assets/file.jpg")
+    ///     .unwrap();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![1]), order)
+    ///     .unwrap();
+    /// data.write_from_file_preserving("assets/file.jpg", "/tmp/preserved.jpg")
+    ///     .unwrap();
+    /// ```
+    pub fn write_preserving(
+        &mut self,
+        old_buffer: impl AsRef<[u8]>,
+        to: impl AsRef<Path>,
+    ) -> Result<(), ExifError> {
+        let exif_data = self.save_data()?;
 
-        // let old_jpeg = std::fs::read(from)?;
         let old_buffer = old_buffer.as_ref();
 
-        let skip = if old_buffer.starts_with(&EXIF_HEADER) || old_buffer.starts_with(&JPEG_HEADER) {
-            // Skip the size of the exif header which is a 2 byte big-endian number
-            //
-            // NOTE: In the original library they take a u32 and the bitshift it by 8
-            // and then and it by 0xff to get the big-endian bytes for a u16 so I just calculated
-            // it as a u16
-            let skip_data: &[u8; 2] = old_buffer[4..=5].try_into().unwrap();
-            u16::from_be_bytes(*skip_data) as usize + 4
+        // Keep every segment of the old JPEG but its EXIF segment, in their original order, and
+        // put the new EXIF segment in its place
+        let (head, tail) = if old_buffer.starts_with(&EXIF_HEADER[..2]) {
+            match jpeg::find_app1_exif(old_buffer) {
+                Some(segment) => (&old_buffer[2..segment.start], &old_buffer[segment.end..]),
+                None => old_buffer[2..].split_at(jpeg::exif_insert_position(old_buffer) - 2),
+            }
         } else {
             // If the user already skipped the headers themselves
-            0
+            (&[][..], old_buffer)
         };
 
-        // Size of the exif header is 4 bytes
-        // and u16::MAX = 65536 so that's 8KiB of data for a single ExifData block
-        // FIXME handle exif size with greater than 8KiB of data
-        // let skip =
-
-        let jpeg_data_old = &old_buffer[skip..];
-        let exif_data_len = exif_data_len as u16 + 2;
+        let exif_data_len = u16::try_from(exif_data.len() + 2)
+            .map_err(|_| ExifError::MalformedOutput("EXIF data doesn't fit in an APP1 segment"))?;
 
         let mut jpeg_buffer = Vec::new();
-        jpeg_buffer.write_all(&EXIF_HEADER)?;
+        jpeg_buffer.write_all(&EXIF_HEADER[..2])?;
+        jpeg_buffer.write_all(head)?;
+        let exif_start = jpeg_buffer.len();
+        jpeg_buffer.write_all(&EXIF_HEADER[2..])?;
         jpeg_buffer.write_all(&exif_data_len.to_be_bytes())?;
-        jpeg_buffer.write_all(exif_data)?;
-        jpeg_buffer.write_all(jpeg_data_old)?;
+        jpeg_buffer.write_all(&exif_data)?;
+        jpeg_buffer.write_all(tail)?;
+
+        crate::jpeg::validate(&jpeg_buffer, exif_start, exif_data.len())?;
 
         let mut file = std::fs::File::create(to)?;
         file.write_all(&jpeg_buffer)?;
         Ok(())
     }
+
+    /// Write the JPEG `original` with the tags of this data added to its own EXIF data, instead
+    /// of replacing it like [`write()`](#method.write) does.
+    ///
+    /// The EXIF data of `original` is read with `FollowSpecification` and `IgnoreUnknownTags`
+    /// disabled and its maker note kept opaque, then every tag this data adds or changes
+    /// compared to it is set, including the values recorded with
+    /// [`set_override()`](#method.set_override). Tags that are only in `original` stay, as do
+    /// the bytes of its maker note, so vendor data the crate doesn't understand survives the
+    /// edit. The result is written like [`write_preserving()`](#method.write_preserving).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let original = std::fs::read("assets/file.jpg").unwrap();
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Someone".into(), order)
+    ///     .unwrap();
+    /// data.write_merging(&original, "/tmp/merged.jpg").unwrap();
+    ///
+    /// let merged = Data::open("/tmp/merged.jpg").unwrap();
+    /// assert!(merged.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).is_ok());
+    /// assert!(merged.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// ```
+    ///
+    /// The maker note is kept even when the EXIF IFD has other opaque entries, such as a
+    /// `DeviceSettingDescription`:
+    /// ```rust
+    /// use libexif::{Data, DataReader, ExifTag, IFD};
+    /// // Little-endian TIFF data: IFD0 pointing at an EXIF IFD with a MakerNote of 8 bytes and
+    /// // a DeviceSettingDescription of 4 bytes
+    /// let mut tiff = Vec::new();
+    /// tiff.extend_from_slice(b"II*\0\x08\0\0\0");
+    /// tiff.extend_from_slice(&[1, 0, 0x69, 0x87, 4, 0, 1, 0, 0, 0, 26, 0, 0, 0, 0, 0, 0, 0]);
+    /// tiff.extend_from_slice(&[2, 0, 0x7c, 0x92, 7, 0, 8, 0, 0, 0, 56, 0, 0, 0]);
+    /// tiff.extend_from_slice(&[0x0b, 0xa4, 7, 0, 4, 0, 0, 0]);
+    /// tiff.extend_from_slice(b"DSD!\0\0\0\0VENDOR\0\x01");
+    /// let len = (2 + 6 + tiff.len()) as u16;
+    /// let original =
+    ///     [&[0xff, 0xd8, 0xff, 0xe1][..], &len.to_be_bytes(), b"Exif\0\0", &tiff, &[0xff, 0xd9]]
+    ///         .concat();
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Someone".into(), order)
+    ///     .unwrap();
+    /// data.write_merging(&original, "/tmp/merged_maker_note.jpg").unwrap();
+    ///
+    /// let merged = DataReader::new()
+    ///     .parse_maker_notes(false)
+    ///     .open("/tmp/merged_maker_note.jpg")
+    ///     .unwrap();
+    /// let maker_note = merged.get_entry(IFD::EXIF, ExifTag::EXIF_TAG_MAKER_NOTE).unwrap();
+    /// assert_eq!(maker_note.raw_data(), b"VENDOR\0\x01");
+    /// let settings = merged
+    ///     .get_entry(IFD::EXIF, ExifTag::EXIF_TAG_DEVICE_SETTING_DESCRIPTION)
+    ///     .unwrap();
+    /// assert_eq!(settings.raw_data(), b"DSD!");
+    /// ```
+    ///
+    /// Tags libexif doesn't know are kept too:
+    /// ```rust
+    /// use libexif::{Data, DataOption, DataReader, ExifTag, IFD};
+    /// // Little-endian TIFF data: IFD0 with a SHORT of 42 in the unknown tag 0xabcd
+    /// let mut tiff = Vec::new();
+    /// tiff.extend_from_slice(b"II*\0\x08\0\0\0");
+    /// tiff.extend_from_slice(&[1, 0, 0xcd, 0xab, 3, 0, 1, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0]);
+    /// let len = (2 + 6 + tiff.len()) as u16;
+    /// let original =
+    ///     [&[0xff, 0xd8, 0xff, 0xe1][..], &len.to_be_bytes(), b"Exif\0\0", &tiff, &[0xff, 0xd9]]
+    ///         .concat();
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Someone".into(), order)
+    ///     .unwrap();
+    /// data.write_merging(&original, "/tmp/merged_unknown.jpg").unwrap();
+    ///
+    /// let merged = DataReader::new()
+    ///     .without_option(DataOption::IgnoreUnknownTags)
+    ///     .without_option(DataOption::FollowSpecification)
+    ///     .open("/tmp/merged_unknown.jpg")
+    ///     .unwrap();
+    /// let unknown = merged.get_entry(IFD::Image, ExifTag(0xabcd)).unwrap();
+    /// assert_eq!(unknown.raw_data(), [42, 0]);
+    /// assert!(merged.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).is_ok());
+    /// ```
+    ///
+    /// Pending overrides are written too:
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let original = std::fs::read("assets/file.jpg").unwrap();
+    ///
+    /// let mut data = Data::new();
+    /// data.set_override(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![6]));
+    /// data.write_merging(&original, "/tmp/merged_override.jpg").unwrap();
+    ///
+    /// let merged = Data::open("/tmp/merged_override.jpg").unwrap();
+    /// assert_eq!(merged.orientation(), Some(6));
+    /// ```
+    pub fn write_merging(
+        &mut self,
+        original: &[u8],
+        to: impl AsRef<Path>,
+    ) -> Result<(), ExifError> {
+        self.apply_overrides()?;
+
+        let mut merged = DataReader::new()
+            .without_option(DataOption::FollowSpecification)
+            .without_option(DataOption::IgnoreUnknownTags)
+            .parse_maker_notes(false)
+            .from_bytes(original)?;
+        let order = merged.byte_order();
+
+        for change in merged.diff(self) {
+            match change {
+                TagChange::Added { ifd, tag, value }
+                | TagChange::Changed {
+                    ifd,
+                    tag,
+                    new: value,
+                    ..
+                } if tag != Tag::from(ExifTag::EXIF_TAG_MAKER_NOTE) => {
+                    merged.set_entry_unchecked(ifd, tag.into(), value, order)?;
+                }
+                _ => {}
+            }
+        }
+
+        merged.write_preserving(original, to)
+    }
+
+    /// Write the EXIF data into a WebP file, `old_webp` being the contents of the original
+    /// file.
+    ///
+    /// The `EXIF` chunk is replaced, or added when there's none. Like
+    /// [`write_preserving()`](#method.write_preserving), the data isn't fixed before it is
+    /// written.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// // A lossless 1x1 WebP, which has no room for EXIF data until it has a VP8X chunk
+    /// let webp = b"RIFF\x12\0\0\0WEBPVP8L\x05\0\0\0\x2f\0\0\0\0\0";
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![6]), order)
+    ///     .unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("libexif-write-webp.webp");
+    /// data.write_webp(webp, &path).unwrap();
+    ///
+    /// assert_eq!(Data::open_webp(&path).unwrap().orientation(), Some(6));
+    ///
+    /// // A lossy 3x2 WebP gets a VP8X chunk with the EXIF flag and the canvas size minus one
+    /// let webp = b"RIFF\x16\0\0\0WEBPVP8 \x0a\0\0\0\x50\x01\0\x9d\x01\x2a\x03\0\x02\0";
+    /// data.write_webp(webp, &path).unwrap();
+    ///
+    /// let written = std::fs::read(&path).unwrap();
+    /// assert_eq!(&written[12..30], b"VP8X\x0a\0\0\0\x08\0\0\0\x02\0\0\x01\0\0");
+    /// assert_eq!(Data::open_webp(&path).unwrap().orientation(), Some(6));
+    /// ```
+    pub fn write_webp(
+        &mut self,
+        old_webp: impl AsRef<[u8]>,
+        to: impl AsRef<Path>,
+    ) -> Result<(), ExifError> {
+        let tiff = self.raw_exif_block()?;
+        let webp = crate::webp::insert_exif(old_webp.as_ref(), &tiff)?;
+
+        std::fs::write(to, webp)?;
+        Ok(())
+    }
+
+    /// Serialize the EXIF data into a bare TIFF block, without the `Exif\0\0` signature of a
+    /// JPEG's APP1 segment.
+    ///
+    /// This is how other containers, such as WebP and HEIF, store EXIF data. The block can be
+    /// read back with [`from_raw_exif_block()`](#method.from_raw_exif_block), which makes it
+    /// easy to copy metadata from one image to another without looking at its tags.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let mut source = Data::open("assets/file.jpg").unwrap();
+    /// let tiff = source.raw_exif_block().unwrap();
+    /// assert!(tiff.starts_with(b"II*\0") || tiff.starts_with(b"MM\0*"));
+    ///
+    /// let copy = Data::from_raw_exif_block(&tiff).unwrap();
+    /// assert!(copy.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// ```
+    pub fn raw_exif_block(&mut self) -> Result<Vec<u8>, ExifError> {
+        let mut exif_data = self.save_data()?;
+        if exif_data.starts_with(jpeg::EXIF_SIGNATURE) {
+            exif_data.drain(..jpeg::EXIF_SIGNATURE.len());
+        }
+
+        Ok(exif_data)
+    }
+
+    /// Create a fully independent copy of the EXIF data.
+    ///
+    /// The copy is made by serializing the data with `exif_data_save_data` and parsing the
+    /// result into a new `ExifData`, so it costs a full save and reload. Nothing is shared with
+    /// the original afterwards, so either one can be modified without affecting the other.
+    ///
+    /// The data is reloaded as it is, without fixing it or dropping unknown tags, and the copy
+    /// gets the options of the original. Pending overrides are copied too rather than set in
+    /// the original, see [`set_override()`](#method.set_override).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let copy = data.clone_deep().unwrap();
+    /// assert!(copy.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// ```
+    ///
+    /// The copy uses the same allocator and decodes text with the same character set:
+    /// ```rust
+    /// use libexif::{Charset, DataReader, ExifTag, IFD, Value};
+    /// // A TIFF block whose Artist is "été" in Latin-1
+    /// let tiff = b"II*\0\x08\0\0\0\x01\0\x3b\x01\x02\0\x04\0\0\0\xe9t\xe9\0\0\0\0\0";
+    /// let data = DataReader::new()
+    ///     .with_charset(Charset::Latin1)
+    ///     .from_raw_exif_block(tiff)
+    ///     .unwrap();
+    ///
+    /// let copy = data.clone_deep().unwrap();
+    /// let artist = copy.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).unwrap();
+    /// assert_eq!(artist.value_le(), Value::Text("été".to_string()));
+    /// ```
+    ///
+    /// Unknown tags and pending overrides are copied without changing the original:
+    /// ```rust
+    /// use libexif::{DataOption, DataReader, ExifTag, IFD, Value};
+    /// // A TIFF block with a SHORT of 42 in the unknown tag 0xabcd
+    /// let tiff = b"II*\0\x08\0\0\0\x01\0\xcd\xab\x03\0\x01\0\0\0\x2a\0\0\0\0\0\0\0";
+    /// let mut data = DataReader::new()
+    ///     .without_option(DataOption::IgnoreUnknownTags)
+    ///     .without_option(DataOption::FollowSpecification)
+    ///     .from_raw_exif_block(tiff)
+    ///     .unwrap();
+    /// data.set_override(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![6]));
+    ///
+    /// let copy = data.clone_deep().unwrap();
+    /// assert!(copy.get_entry(IFD::Image, ExifTag(0xabcd)).is_ok());
+    /// let orientation = copy.get_value(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION);
+    /// assert_eq!(orientation.unwrap(), Value::U16(vec![6]));
+    ///
+    /// // The override is still pending in the original
+    /// assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION).is_err());
+    /// ```
+    pub fn clone_deep(&self) -> Result<Data, ExifError> {
+        let buffer = self.serialize()?;
+
+        let ptr = unsafe { exif_data_new_mem(self.mem.as_ptr()) };
+        if ptr.is_null() {
+            return Err(ExifError::ExifDataNull);
+        }
+
+        let mut data = Data::from_libexif(ptr)
+            .with_allocator(self.mem.clone())
+            .with_charset(self.charset);
+        data.options = self.options.clone();
+        data.overrides = self.overrides.clone();
+
+        // Load the data as it was serialized, then take the options of the original
+        data.apply_options();
+        data.unset_option(DataOption::IgnoreUnknownTags)
+            .unset_option(DataOption::FollowSpecification);
+        unsafe { exif_data_load_data(ptr, buffer.as_ptr(), buffer.len() as c_uint) };
+        data.options = self.options.clone();
+        data.apply_options();
+
+        data.set_encoding(self.encoding());
+        Ok(data)
+    }
+
+    /// Serialize the EXIF data, returning the `Exif\0\0` prefixed block libexif produced.
+    fn save_data(&mut self) -> Result<Vec<u8>, ExifError> {
+        self.apply_overrides()?;
+        self.serialize()
+    }
+
+    /// Same as [`save_data()`](#method.save_data) without applying the overrides.
+    fn serialize(&self) -> Result<Vec<u8>, ExifError> {
+        let mut exif_data: *mut u8 = std::ptr::null_mut();
+        let mut exif_data_len: c_uint = 0;
+        unsafe {
+            exif_data_save_data(
+                self.inner as *const _ as *mut _,
+                &mut exif_data,
+                &mut exif_data_len,
+            );
+        }
+        if exif_data.is_null() {
+            return Err(ExifError::ExifDataNull);
+        }
+
+        // libexif allocates the buffer with the data's allocator so copy it out and free it
+        let buffer = unsafe { slice::from_raw_parts(exif_data, exif_data_len as usize) }.to_vec();
+        unsafe {
+            exif_mem_free(self.mem.as_ptr(), exif_data as *mut libc::c_void);
+        }
+
+        if buffer.is_empty() {
+            return Err(ExifError::ExifDataLenZero);
+        }
+        Ok(buffer)
+    }
+}
+
+/// The options libexif enables on new data.
+fn default_options() -> HashSet<DataOption> {
+    HashSet::from([
+        DataOption::IgnoreUnknownTags,
+        DataOption::FollowSpecification,
+    ])
+}
+
+/// Whether libexif records the tag in the given IFD.
+fn tag_known_in_ifd(tag: ExifTag, ifd: IFD) -> bool {
+    let known = |ifd: IFD| !unsafe { exif_tag_get_title_in_ifd(tag, ifd.to_libexif()) }.is_null();
+
+    // libexif's tag table only marks a handful of tags as recorded in IFD1, but IFD1 is a TIFF
+    // directory just like IFD0 and holds the same kind of tags for the thumbnail
+    known(ifd) || (ifd == IFD::Thumbnail && known(IFD::Image))
 }
 
 struct Contents<'a> {
     contents: &'a [*mut ExifContent],
     index: usize,
+    charset: Charset,
+    mem: &'a Allocator,
 }
 
 impl<'a> Iterator for Contents<'a> {
@@ -350,7 +1879,11 @@ impl<'a> Iterator for Contents<'a> {
             let content = self.contents[self.index];
             self.index += 1;
 
-            Some(Content::from_libexif(unsafe { &mut *content }))
+            Some(
+                Content::from_libexif(unsafe { &mut *content })
+                    .with_charset(self.charset)
+                    .with_allocator(self.mem),
+            )
         } else {
             None
         }
@@ -0,0 +1,237 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::bindings::{ExifTag, EXIF_TAG_GPS_DATE_STAMP, EXIF_TAG_GPS_TIME_STAMP};
+use crate::bits::*;
+use crate::data::Data;
+use crate::error::ExifError;
+use crate::value::{Rational, Value};
+
+/// Format of the EXIF `DateTime*` tags.
+const DATETIME_FORMAT: &str = "%Y:%m:%d %H:%M:%S";
+
+/// Format of the `GPSDateStamp` tag.
+const GPS_DATE_FORMAT: &str = "%Y:%m:%d";
+
+macro_rules! datetime_tags {
+    (
+        $(
+            $name: ident => $ifd: ident, $tag: ident, $title: literal, $meaning: literal,
+        )*
+    ) => {
+        $(
+            paste! {
+                #[doc = concat!("Returns the `", $title, "`, ", $meaning, ".")]
+                pub fn $name(&self) -> Option<NaiveDateTime> {
+                    self.get_datetime_tag(IFD::$ifd, ExifTag::$tag)
+                }
+
+                #[doc = concat!("Sets the `", $title, "`, ", $meaning, ".")]
+                ///
+                /// Any fraction of a second is ignored.
+                pub fn [<set_$name>](&mut self, datetime: NaiveDateTime) -> Result<(), ExifError> {
+                    self.set_datetime_tag(IFD::$ifd, ExifTag::$tag, datetime)
+                }
+            }
+        )*
+    };
+}
+
+/// EXIF has three date and time tags, each one is read and written in the canonical
+/// `YYYY:MM:DD HH:MM:SS` format by its own methods.
+///
+/// # Example
+/// ```rust
+/// use chrono::NaiveDate;
+/// use libexif::Data;
+///
+/// let mut data = Data::new();
+/// let taken = NaiveDate::from_ymd_opt(2021, 7, 14)
+///     .unwrap()
+///     .and_hms_opt(18, 30, 5)
+///     .unwrap();
+/// data.set_datetime_original(taken).unwrap();
+///
+/// assert_eq!(data.datetime_original(), Some(taken));
+/// assert_eq!(data.datetime(), None);
+/// ```
+impl Data {
+    datetime_tags! {
+        datetime => Image, EXIF_TAG_DATE_TIME, "DateTime",
+            "when the file was last changed (IFD0)",
+        datetime_original => EXIF, EXIF_TAG_DATE_TIME_ORIGINAL, "DateTimeOriginal",
+            "when the photo was taken",
+        datetime_digitized => EXIF, EXIF_TAG_DATE_TIME_DIGITIZED, "DateTimeDigitized",
+            "when the photo was stored digitally",
+    }
+
+    /// Returns the `DateTimeOriginal` refined by `SubSecTimeOriginal`, as the date and time
+    /// along with the milliseconds.
+    ///
+    /// `SubSecTimeOriginal` holds the decimal digits of the fraction of a second, so `"5"` is
+    /// 500 milliseconds and `"0512"` is 51 milliseconds. When it is missing or malformed the
+    /// milliseconds are 0.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chrono::NaiveDate;
+    /// use libexif::Data;
+    ///
+    /// let mut data = Data::new();
+    /// let datetime = NaiveDate::from_ymd_opt(2021, 7, 14)
+    ///     .unwrap()
+    ///     .and_hms_opt(18, 30, 5)
+    ///     .unwrap();
+    /// data.set_datetime_original_with_subsec(datetime, 42).unwrap();
+    ///
+    /// assert_eq!(data.datetime_original_with_subsec(), Some((datetime, 42)));
+    /// ```
+    pub fn datetime_original_with_subsec(&self) -> Option<(NaiveDateTime, u32)> {
+        let datetime = self.datetime_original()?;
+
+        let millis = self
+            .get_text(IFD::EXIF, ExifTag::EXIF_TAG_SUB_SEC_TIME_ORIGINAL)
+            .and_then(|subsec| parse_subsec_millis(&subsec))
+            .unwrap_or(0);
+
+        Some((datetime, millis))
+    }
+
+    /// Sets the `DateTimeOriginal` and `SubSecTimeOriginal` tags, see
+    /// [`datetime_original_with_subsec()`](#method.datetime_original_with_subsec).
+    ///
+    /// Any fraction of a second in `datetime` is ignored, the sub-second part is written from
+    /// `millis` which has to be below 1000.
+    pub fn set_datetime_original_with_subsec(
+        &mut self,
+        datetime: NaiveDateTime,
+        millis: u32,
+    ) -> Result<(), ExifError> {
+        if millis >= 1000 {
+            return Err(ExifError::SubSecOutOfRange(millis));
+        }
+
+        let order = self.byte_order();
+        self.set_datetime_original(datetime)?;
+        self.set_entry(
+            IFD::EXIF,
+            ExifTag::EXIF_TAG_SUB_SEC_TIME_ORIGINAL,
+            Value::Text(format!("{:03}", millis)),
+            order,
+        )
+    }
+
+    /// Returns the UTC date and time of the GPS fix, from `GPSDateStamp` and `GPSTimeStamp`.
+    ///
+    /// The seconds of `GPSTimeStamp` may have a fraction, which is kept up to nanoseconds.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chrono::NaiveDate;
+    /// use libexif::Data;
+    ///
+    /// let mut data = Data::new();
+    /// let fix = NaiveDate::from_ymd_opt(2021, 7, 14)
+    ///     .unwrap()
+    ///     .and_hms_milli_opt(16, 30, 5, 250)
+    ///     .unwrap();
+    /// data.set_gps_datetime(fix).unwrap();
+    ///
+    /// assert_eq!(data.gps_datetime(), Some(fix));
+    /// ```
+    pub fn gps_datetime(&self) -> Option<NaiveDateTime> {
+        let date = self.get_text(IFD::GPS, ExifTag(EXIF_TAG_GPS_DATE_STAMP))?;
+        let date = NaiveDate::parse_from_str(date.trim(), GPS_DATE_FORMAT).ok()?;
+
+        let entry = self
+            .get_entry(IFD::GPS, ExifTag(EXIF_TAG_GPS_TIME_STAMP))
+            .ok()?;
+        let [hours, minutes, seconds] = entry.value_auto().ok()?.as_array_urational::<3>()?;
+        if hours.1 == 0 || minutes.1 == 0 || seconds.1 == 0 {
+            return None;
+        }
+
+        let nanos = u64::from(seconds.0) * 1_000_000_000 / u64::from(seconds.1);
+        let time = NaiveTime::from_hms_nano_opt(
+            hours.0 / hours.1,
+            minutes.0 / minutes.1,
+            (nanos / 1_000_000_000) as u32,
+            (nanos % 1_000_000_000) as u32,
+        )?;
+
+        Some(date.and_time(time))
+    }
+
+    /// Sets the `GPSDateStamp` and `GPSTimeStamp` tags to a UTC date and time, see
+    /// [`gps_datetime()`](#method.gps_datetime).
+    ///
+    /// The fraction of a second is written in milliseconds.
+    pub fn set_gps_datetime(&mut self, datetime: NaiveDateTime) -> Result<(), ExifError> {
+        let order = self.byte_order();
+        let millis = datetime.nanosecond() / 1_000_000;
+        let seconds = match millis {
+            0 => Rational(datetime.second(), 1),
+            _ => Rational(datetime.second() * 1000 + millis, 1000),
+        };
+
+        self.set_entry(
+            IFD::GPS,
+            ExifTag(EXIF_TAG_GPS_DATE_STAMP),
+            Value::Text(datetime.format(GPS_DATE_FORMAT).to_string()),
+            order,
+        )?;
+        self.set_entry(
+            IFD::GPS,
+            ExifTag(EXIF_TAG_GPS_TIME_STAMP),
+            Value::URational(vec![
+                Rational(datetime.hour(), 1),
+                Rational(datetime.minute(), 1),
+                seconds,
+            ]),
+            order,
+        )
+    }
+
+    fn get_datetime_tag(&self, ifd: IFD, tag: ExifTag) -> Option<NaiveDateTime> {
+        let datetime = self.get_text(ifd, tag)?;
+        NaiveDateTime::parse_from_str(datetime.trim(), DATETIME_FORMAT).ok()
+    }
+
+    fn set_datetime_tag(
+        &mut self,
+        ifd: IFD,
+        tag: ExifTag,
+        datetime: NaiveDateTime,
+    ) -> Result<(), ExifError> {
+        let order = self.byte_order();
+        self.set_entry(
+            ifd,
+            tag,
+            Value::Text(datetime.format(DATETIME_FORMAT).to_string()),
+            order,
+        )
+    }
+
+    fn get_text(&self, ifd: IFD, tag: ExifTag) -> Option<String> {
+        match self.get_entry(ifd, tag).ok()?.value_auto().ok()? {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the digits of a fraction of a second to milliseconds.
+fn parse_subsec_millis(subsec: &str) -> Option<u32> {
+    // Some cameras pad the value with spaces
+    let digits = subsec.trim();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let millis = digits
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(3)
+        .fold(0, |millis, digit| millis * 10 + u32::from(digit - b'0'));
+
+    Some(millis)
+}
@@ -0,0 +1,152 @@
+use crate::bits::IFD;
+use crate::data::Data;
+use crate::error::ExifError;
+use crate::internal::*;
+use crate::tag::Tag;
+use crate::value::Value;
+
+/// A difference between two EXIF data, as returned by
+/// [`Data::diff()`](struct.Data.html#method.diff).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagChange {
+    /// The tag is only in the new data.
+    Added { ifd: IFD, tag: Tag, value: Value },
+
+    /// The tag is only in the old data.
+    Removed { ifd: IFD, tag: Tag },
+
+    /// The tag has another value in the new data.
+    Changed {
+        ifd: IFD,
+        tag: Tag,
+        old: Value,
+        new: Value,
+    },
+}
+
+/// How [`Data::merge()`](struct.Data.html#method.merge) resolves a tag that both data have with
+/// different values. Tags only in the other data are always added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// Take the value of the other data.
+    Overwrite,
+
+    /// Keep the value of this data.
+    KeepExisting,
+
+    /// Take the value of the other data only when the value of this data is blank: empty text,
+    /// or zeros only.
+    PreferNonEmpty,
+}
+
+impl Data {
+    /// List the tags that were added, removed or changed going from this data to `other`.
+    ///
+    /// Values are compared once decoded, like [`exif_eq()`](#method.exif_eq) does. The changes
+    /// are sorted by IFD and tag ID.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, TagChange, Value};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    /// let mut edited = data.clone_deep().unwrap();
+    /// let order = edited.byte_order();
+    /// edited
+    ///     .set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, Value::Text("Jane".into()), order)
+    ///     .unwrap();
+    ///
+    /// let changes = data.diff(&edited);
+    /// assert!(matches!(
+    ///     &changes[..],
+    ///     [TagChange::Added { ifd: IFD::Image, .. }]
+    /// ));
+    /// ```
+    pub fn diff(&self, other: &Data) -> Vec<TagChange> {
+        let old = self.entry_values();
+        let mut new = other.entry_values();
+
+        let mut changes = Vec::new();
+        for ((ifd, tag), old) in old {
+            match new.remove(&(ifd, tag)) {
+                Some(new) if new == old => {}
+                Some(new) => changes.push(TagChange::Changed { ifd, tag, old, new }),
+                None => changes.push(TagChange::Removed { ifd, tag }),
+            }
+        }
+        changes.extend(new.into_iter().map(|((ifd, tag), value)| TagChange::Added {
+            ifd,
+            tag,
+            value,
+        }));
+
+        changes.sort_by_key(|change| {
+            let (TagChange::Added { ifd, tag, .. }
+            | TagChange::Removed { ifd, tag }
+            | TagChange::Changed { ifd, tag, .. }) = change;
+            (ifd.to_libexif() as u32, tag.id())
+        });
+        changes
+    }
+
+    /// Add the tags of `other` to this data, resolving the tags both have with different values
+    /// according to `policy`.
+    ///
+    /// This is meant for applying a template of defaults to a file or the other way around, e.g.,
+    /// copying the artist and copyright into the EXIF data of a photo.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, MergePolicy, Value};
+    /// let mut data = Data::open("assets/file.jpg").unwrap();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "".into(), order).unwrap();
+    ///
+    /// let mut template = Data::new();
+    /// let order = template.byte_order();
+    /// template.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Jane".into(), order).unwrap();
+    ///
+    /// let artist = |data: &Data| {
+    ///     let value = data.get_value(IFD::Image, ExifTag::EXIF_TAG_ARTIST).unwrap();
+    ///     value.text_trimmed().unwrap()
+    /// };
+    ///
+    /// data.merge(&template, MergePolicy::KeepExisting).unwrap();
+    /// assert_eq!(artist(&data), "");
+    ///
+    /// data.merge(&template, MergePolicy::PreferNonEmpty).unwrap();
+    /// assert_eq!(artist(&data), "Jane");
+    /// ```
+    pub fn merge(&mut self, other: &Data, policy: MergePolicy) -> Result<(), ExifError> {
+        let order = self.byte_order();
+
+        for change in self.diff(other) {
+            let (ifd, tag, value) = match change {
+                TagChange::Added { ifd, tag, value } => (ifd, tag, value),
+                TagChange::Changed { ifd, tag, old, new } => match policy {
+                    MergePolicy::Overwrite => (ifd, tag, new),
+                    MergePolicy::PreferNonEmpty if is_blank(&old) => (ifd, tag, new),
+                    MergePolicy::KeepExisting | MergePolicy::PreferNonEmpty => continue,
+                },
+                TagChange::Removed { .. } => continue,
+            };
+            self.set_entry_unchecked(ifd, tag.into(), value, order)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the value carries no information: empty text, or zeros only.
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Text(text) => text.trim_end_matches(['\0', ' ']).is_empty(),
+        Value::U8(values) | Value::Undefined(values) => values.iter().all(|&v| v == 0),
+        Value::I8(values) => values.iter().all(|&v| v == 0),
+        Value::U16(values) => values.iter().all(|&v| v == 0),
+        Value::I16(values) => values.iter().all(|&v| v == 0),
+        Value::U32(values) => values.iter().all(|&v| v == 0),
+        Value::I32(values) => values.iter().all(|&v| v == 0),
+        Value::URational(values) => values.iter().all(|v| v.numerator() == 0),
+        Value::IRational(values) => values.iter().all(|v| v.numerator() == 0),
+    }
+}
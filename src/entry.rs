@@ -1,4 +1,6 @@
+use std::cell::OnceCell;
 use std::ffi::CString;
+use std::ops::Deref;
 use std::slice;
 
 use libc::{self, c_char, c_uint};
@@ -7,6 +9,8 @@ use crate::bindings::*;
 use crate::bits::*;
 use crate::error::ExifError;
 use crate::internal::*;
+use crate::mem::Allocator;
+use crate::reader::Charset;
 use crate::tag::Tag;
 use crate::value::Value;
 
@@ -14,11 +18,17 @@ use crate::value::Value;
 #[derive(Debug)]
 pub struct Entry<'a> {
     inner: &'a mut ExifEntry,
+    charset: Charset,
+    cache: OnceCell<Value>,
 }
 
 impl<'a> FromLibExif<&'a mut ExifEntry> for Entry<'a> {
     fn from_libexif(entry: &'a mut ExifEntry) -> Self {
-        Entry { inner: entry }
+        Entry {
+            inner: entry,
+            charset: Charset::default(),
+            cache: OnceCell::new(),
+        }
     }
 }
 
@@ -29,11 +39,66 @@ impl<'a> ToLibExif<ExifEntry> for Entry<'a> {
 }
 
 impl<'a> Entry<'a> {
+    /// Use the given character set when decoding the text of `ASCII` tags.
+    pub(crate) fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
     /// EXIF tag for the entry.
     pub fn tag(&self) -> Tag {
         Tag::from_libexif(self.inner.tag)
     }
 
+    /// The IFD the entry belongs to, or `None` when the entry isn't part of any IFD.
+    pub fn ifd(&self) -> Option<IFD> {
+        let content = self.inner.parent;
+        if content.is_null() {
+            return None;
+        }
+
+        Some(IFD::from_libexif(unsafe { exif_content_get_ifd(content) }))
+    }
+
+    /// Whether libexif has a name for the entry's tag in the IFD the entry belongs to.
+    ///
+    /// Vendor extensions and other proprietary tags aren't known, calling
+    /// [`Tag::name()`](struct.Tag.html#method.name) or
+    /// [`Tag::title()`](struct.Tag.html#method.title) for them panics.
+    pub fn is_known_tag(&self) -> bool {
+        match self.ifd() {
+            Some(ifd) => {
+                !unsafe { exif_tag_get_name_in_ifd(self.inner.tag, ifd.to_libexif()) }.is_null()
+            }
+            None => false,
+        }
+    }
+
+    /// The entry's tag, its numeric ID and its title in the IFD the entry belongs to, e.g., for
+    /// the rows of a table of entries.
+    ///
+    /// The title is empty when the tag [isn't known](#method.is_known_tag).
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).unwrap();
+    /// let (tag, id, title) = entry.tag_info();
+    /// assert_eq!(tag, entry.tag());
+    /// assert_eq!(id, 0x011a);
+    /// assert_eq!(title, "X-Resolution");
+    /// ```
+    pub fn tag_info(&self) -> (Tag, u16, &'static str) {
+        let tag = self.tag();
+        let title = match self.ifd() {
+            Some(ifd) if self.is_known_tag() => tag.title(ifd),
+            _ => "",
+        };
+
+        (tag, tag.id(), title)
+    }
+
     /// Type of data contained in the entry.
     pub fn data_type(&self) -> DataType {
         DataType::from_libexif(self.inner.format)
@@ -45,10 +110,38 @@ impl<'a> Entry<'a> {
     }
 
     /// Return the raw binary data for the entry's value.
+    ///
+    /// The bytes are returned as they are stored, in the byte order of the EXIF data the entry
+    /// belongs to. Multi-byte values such as `U16` or `URational` arrays still need to be swapped
+    /// on big-endian data, use [`value()`](#method.value) to decode them instead.
     pub fn raw_data(&self) -> &[u8] {
+        if self.inner.data.is_null() {
+            return &[];
+        }
+
         unsafe { slice::from_raw_parts(self.inner.data, self.inner.size as usize) }
     }
 
+    /// Whether the value is stored in the IFD entry itself, which holds up to 4 bytes, rather
+    /// than at an offset the entry points at.
+    ///
+    /// libexif hides the difference when reading and writing, this is meant for matching
+    /// entries with the raw TIFF bytes, e.g., those
+    /// [`Data::dump_offsets()`](struct.Data.html#method.dump_offsets) describes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let unit = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_RESOLUTION_UNIT).unwrap();
+    /// assert!(unit.is_inline());
+    /// let x_resolution = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).unwrap();
+    /// assert!(!x_resolution.is_inline());
+    /// ```
+    pub fn is_inline(&self) -> bool {
+        self.inner.size <= 4
+    }
+
     /// Returns an interpreted value of the entry's data.
     pub fn value(&self, byte_order: ByteOrder) -> Value {
         Value::extract(
@@ -56,9 +149,201 @@ impl<'a> Entry<'a> {
             self.data_type(),
             self.components(),
             byte_order,
+            self.charset,
         )
     }
 
+    /// Returns an interpreted value of the entry's data, reading it as little-endian ("Intel").
+    pub fn value_le(&self) -> Value {
+        self.value(ByteOrder::LittleEndian)
+    }
+
+    /// Returns an interpreted value of the entry's data, reading it as big-endian ("Motorola").
+    pub fn value_be(&self) -> Value {
+        self.value(ByteOrder::BigEndian)
+    }
+
+    /// Returns an interpreted value of the entry's data using the byte order of the EXIF data
+    /// the entry belongs to.
+    ///
+    /// Unlike [`value()`](#method.value) this doesn't panic when the size of the data doesn't
+    /// match the entry's format and number of components. Entries whose format isn't modeled by
+    /// [`DataType`](enum.DataType.html), such as `FLOAT` or `DOUBLE`, fail with
+    /// `UnsupportedFormat` instead of being decoded as `Undefined` bytes.
+    pub fn value_auto(&self) -> Result<Value, ExifError> {
+        let byte_order = self.parent_byte_order()?;
+        self.validate_type()?;
+
+        Ok(self.value(byte_order))
+    }
+
+    /// Check that the entry's format is one the crate models and that the size of its data
+    /// matches the format and number of components.
+    ///
+    /// Malformed files can have entries such as a `RATIONAL` whose data isn't a multiple of 8
+    /// bytes, which [`value()`](#method.value) can't decode. Those fail with
+    /// `EntrySizeMismatch`, formats like `FLOAT` fail with `UnsupportedFormat`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Data;
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// for content in data.contents() {
+    ///     for entry in content.entries() {
+    ///         if let Err(err) = entry.validate_type() {
+    ///             eprintln!("{:?} is malformed: {}", entry.tag(), err);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn validate_type(&self) -> Result<(), ExifError> {
+        let data_type = DataType::try_from_libexif(self.inner.format)?;
+
+        let expected = data_type.size().saturating_mul(self.components());
+        let found = self.raw_data().len();
+        if expected != found {
+            return Err(ExifError::EntrySizeMismatch { expected, found });
+        }
+
+        Ok(())
+    }
+
+    /// Same as [`Value::text_checked()`](enum.Value.html#method.text_checked) on the entry's
+    /// raw data.
+    ///
+    /// Decoding a `Text` value replaces invalid UTF-8 with `U+FFFD`, which loses the original
+    /// bytes, so the raw data is checked instead of the value. Entries of other types return
+    /// their raw bytes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Jane Doe".into(), order)
+    ///     .unwrap();
+    ///
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).unwrap();
+    /// assert_eq!(entry.text_checked(), Ok("Jane Doe".to_string()));
+    /// ```
+    pub fn text_checked(&self) -> Result<String, Vec<u8>> {
+        let bytes = self.raw_data().to_vec();
+        match DataType::try_from_libexif(self.inner.format) {
+            Ok(DataType::Text | DataType::U8 | DataType::Undefined) => {
+                Value::U8(bytes).text_checked()
+            }
+            _ => Err(bytes),
+        }
+    }
+
+    /// Decode the entry like [`value_auto()`](#method.value_auto) and convert every component
+    /// to `f64`, integers as they are and rationals by dividing them.
+    ///
+    /// `Text` and `Undefined` entries, and entries that can't be decoded, return an empty
+    /// vector. A rational with a zero denominator becomes `NaN` or an infinity.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).unwrap();
+    /// assert_eq!(entry.as_f64_vec().len(), 1);
+    /// ```
+    pub fn as_f64_vec(&self) -> Vec<f64> {
+        let value = match self.value_auto() {
+            Ok(value) => value,
+            Err(_) => return Vec::new(),
+        };
+
+        match value {
+            Value::U8(values) => values.into_iter().map(f64::from).collect(),
+            Value::I8(values) => values.into_iter().map(f64::from).collect(),
+            Value::U16(values) => values.into_iter().map(f64::from).collect(),
+            Value::I16(values) => values.into_iter().map(f64::from).collect(),
+            Value::U32(values) => values.into_iter().map(f64::from).collect(),
+            Value::I32(values) => values.into_iter().map(f64::from).collect(),
+            Value::URational(values) => values
+                .into_iter()
+                .map(|value| f64::from(value.0) / f64::from(value.1))
+                .collect(),
+            Value::IRational(values) => values
+                .into_iter()
+                .map(|value| f64::from(value.0) / f64::from(value.1))
+                .collect(),
+            Value::Text(_) | Value::Undefined(_) => Vec::new(),
+        }
+    }
+
+    /// Same as [`value_auto()`](#method.value_auto) but the value is only decoded the first
+    /// time, later calls return the same value.
+    ///
+    /// The value is cached under the byte order of the EXIF data the entry belongs to, errors
+    /// aren't cached.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).unwrap();
+    ///
+    /// if let Value::URational(_) = entry.cached_value().unwrap() {
+    ///     println!("{:?}", entry.cached_value().unwrap());
+    /// }
+    /// ```
+    pub fn cached_value(&self) -> Result<&Value, ExifError> {
+        if let Some(value) = self.cache.get() {
+            return Ok(value);
+        }
+
+        let value = self.value_auto()?;
+        Ok(self.cache.get_or_init(|| value))
+    }
+
+    /// Copy the entry into an [`OwnedEntry`](struct.OwnedEntry.html), which can outlive the
+    /// [`Data`](struct.Data.html) the entry belongs to.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD};
+    /// let entry = {
+    ///     let data = Data::open("assets/file.jpg").unwrap();
+    ///     let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).unwrap();
+    ///     entry.clone_owned()
+    /// };
+    ///
+    /// assert_eq!(entry.ifd(), Some(IFD::Image));
+    /// println!("{:?}", entry.value_auto().unwrap());
+    /// ```
+    pub fn clone_owned(&self) -> OwnedEntry {
+        OwnedEntry {
+            tag: self.tag(),
+            ifd: self.ifd(),
+            data_type: self.data_type(),
+            format: self.inner.format,
+            components: self.components(),
+            data: self.raw_data().to_vec(),
+            byte_order: self.parent_byte_order().ok(),
+            charset: self.charset,
+        }
+    }
+
+    /// Byte order of the EXIF data the entry belongs to.
+    fn parent_byte_order(&self) -> Result<ByteOrder, ExifError> {
+        let content = self.inner.parent;
+        if content.is_null() {
+            return Err(ExifError::EntryNoParent);
+        }
+
+        let data = unsafe { (*content).parent };
+        if data.is_null() {
+            return Err(ExifError::EntryNoParent);
+        }
+
+        Ok(ByteOrder::from_libexif(unsafe {
+            exif_data_get_byte_order(data)
+        }))
+    }
+
     /// Returns a textual representation of the entry's data.
     pub fn text_value(&self) -> Result<String, ExifError> {
         let mut buffer = Vec::<u8>::with_capacity(256);
@@ -81,3 +366,184 @@ impl<'a> Entry<'a> {
         Ok(self.inner.format)
     }
 }
+
+/// A copy of an [`Entry`](struct.Entry.html) that doesn't borrow the
+/// [`Data`](struct.Data.html) it was found in, as returned by
+/// [`Entry::clone_owned()`](struct.Entry.html#method.clone_owned).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedEntry {
+    tag: Tag,
+    ifd: Option<IFD>,
+    data_type: DataType,
+    format: ExifFormat,
+    components: usize,
+    data: Vec<u8>,
+    byte_order: Option<ByteOrder>,
+    charset: Charset,
+}
+
+impl OwnedEntry {
+    /// EXIF tag for the entry.
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// The IFD the entry belonged to.
+    pub fn ifd(&self) -> Option<IFD> {
+        self.ifd
+    }
+
+    /// Type of data contained in the entry.
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// Format of the entry as libexif reported it, which is kept even when
+    /// [`data_type()`](#method.data_type) is `Undefined` because the crate doesn't model it.
+    pub fn format(&self) -> ExifFormat {
+        self.format
+    }
+
+    /// Number of data elements in the entry.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Return the raw binary data for the entry's value, see
+    /// [`Entry::raw_data()`](struct.Entry.html#method.raw_data).
+    pub fn raw_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns an interpreted value of the entry's data.
+    pub fn value(&self, byte_order: ByteOrder) -> Value {
+        Value::extract(
+            &self.data,
+            self.data_type,
+            self.components,
+            byte_order,
+            self.charset,
+        )
+    }
+
+    /// Returns an interpreted value of the entry's data using the byte order of the EXIF data
+    /// the entry was copied from, see [`Entry::value_auto()`](struct.Entry.html#method.value_auto).
+    pub fn value_auto(&self) -> Result<Value, ExifError> {
+        let byte_order = self.byte_order.ok_or(ExifError::EntryNoParent)?;
+        DataType::try_from_libexif(self.format)?;
+
+        let expected = self.data_type.size() * self.components;
+        let found = self.data.len();
+        if expected != found {
+            return Err(ExifError::EntrySizeMismatch { expected, found });
+        }
+
+        Ok(self.value(byte_order))
+    }
+}
+
+/// An [`Entry`](struct.Entry.html) whose value can be replaced in place, as returned by
+/// [`Content::entries_mut()`](struct.Content.html#method.entries_mut).
+#[derive(Debug)]
+pub struct EntryMut<'a> {
+    entry: Entry<'a>,
+    mem: Option<&'a Allocator>,
+}
+
+impl<'a> EntryMut<'a> {
+    pub(crate) fn new(entry: Entry<'a>, mem: Option<&'a Allocator>) -> Self {
+        EntryMut { entry, mem }
+    }
+
+    /// Replace the entry's value, encoding it with the byte order of the EXIF data the entry
+    /// belongs to.
+    ///
+    /// The value must have the same format as the entry, the number of components may change.
+    pub fn set_value(&mut self, value: Value) -> Result<(), ExifError> {
+        let order = self.entry.parent_byte_order()?;
+        self.entry.cache.take();
+        overwrite_value(self.entry.inner, value, order, self.mem)
+    }
+}
+
+impl<'a> Deref for EntryMut<'a> {
+    type Target = Entry<'a>;
+
+    fn deref(&self) -> &Entry<'a> {
+        &self.entry
+    }
+}
+
+/// Replace the value of an existing entry, allocating new data when the size changes.
+///
+/// The data is allocated with the allocator of the EXIF data the entry belongs to, libexif's
+/// default one when it's `None`. A new buffer is allocated rather than reallocating the old
+/// one, which custom allocators don't have to support, and the entry is only updated once the
+/// value was encoded, so it's left unchanged when that fails.
+pub(crate) fn overwrite_value(
+    entry: &mut ExifEntry,
+    value: Value,
+    order: ByteOrder,
+    mem: Option<&Allocator>,
+) -> Result<(), ExifError> {
+    // First calculate the components, size, and format of the value
+    let (components, size, format) = value.get_components_size_format()?;
+
+    // Check if the format matches the entry
+    if entry.format != format {
+        return Err(ExifError::FormatMismatch(entry.format, format));
+    }
+
+    let len = (components * size) as u32;
+    if entry.size == len {
+        value.insert(*entry, components, order)?;
+        entry.components = components as _;
+        return Ok(());
+    }
+
+    let default;
+    let mem = match mem {
+        Some(mem) => mem,
+        None => {
+            default = Allocator::default();
+            &default
+        }
+    };
+
+    let buf = match len {
+        0 => std::ptr::null_mut(),
+        _ => unsafe { exif_mem_alloc(mem.as_ptr(), len) as *mut u8 },
+    };
+    if len > 0 && buf.is_null() {
+        return Err(ExifError::BufNewFail);
+    }
+
+    let mut resized = *entry;
+    resized.data = buf;
+    resized.size = len;
+    resized.components = components as _;
+
+    // An empty value has no data to encode
+    let result = match len {
+        0 => Ok(()),
+        _ => value.insert(resized, components, order),
+    };
+    if let Err(err) = result {
+        free(mem, buf);
+        return Err(err);
+    }
+
+    free(mem, entry.data);
+    entry.data = buf;
+    entry.size = len;
+    entry.components = components as _;
+
+    Ok(())
+}
+
+/// Free entry data allocated with the given allocator, unless there's none.
+fn free(mem: &Allocator, data: *mut u8) {
+    if !data.is_null() {
+        unsafe { exif_mem_free(mem.as_ptr(), data as *mut libc::c_void) };
+    }
+}
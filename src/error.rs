@@ -1,5 +1,5 @@
 use crate::bindings::{ExifFormat, ExifTag};
-use crate::bits::IFD;
+use crate::bits::{ByteOrder, IFD};
 #[derive(thiserror::Error, Debug)]
 pub enum ExifError {
     #[error("Entry was not found")]
@@ -20,12 +20,46 @@ pub enum ExifError {
     Utf8Limit,
     #[error("Format mistmatch: expected {0:?} found {1:?}")]
     FormatMismatch(ExifFormat, ExifFormat),
+    #[error("Format {0:?} is not supported")]
+    UnsupportedFormat(ExifFormat),
+    #[error("{0:?} is not an IFD of the EXIF data")]
+    InvalidIfd(IFD),
+    #[error("Value is encoded as {found:?} but the EXIF data is {expected:?}")]
+    ByteOrderMismatch {
+        expected: ByteOrder,
+        found: ByteOrder,
+    },
+    #[error("Text takes {found} bytes with its NUL but the tag has room for {max}")]
+    TextTooLong { max: usize, found: usize },
+    #[error("{name:?} is not a {kind}")]
+    UnknownName { kind: &'static str, name: String },
+    #[error("{0} can't be stored as an unsigned rational")]
+    UnrepresentableRational(f64),
     #[error("Tag {0:?} is not present in IFD {1:?}")]
     TagNotInIfd(ExifTag, IFD),
     #[error("Exif Data length was zero")]
     ExifDataLenZero,
     #[error("Exif Data was null")]
     ExifDataNull,
+    #[error("Entry doesn't belong to any EXIF data")]
+    EntryNoParent,
+    #[error("Entry data is {found} bytes but its format and components need {expected}")]
+    EntrySizeMismatch { expected: usize, found: usize },
+    #[error("Sub-second value of {0} milliseconds is not below a second")]
+    SubSecOutOfRange(u32),
+    #[error("Data wasn't opened with its source file retained")]
+    NoSourceBuffer,
+    #[error("Output JPEG is malformed: {0}")]
+    MalformedOutput(&'static str),
+    #[error("EXIF segment is truncated: {available} of its {expected} bytes are present")]
+    TruncatedExif { expected: usize, available: usize },
+    #[error("Invalid HEIF file: {0}")]
+    InvalidHeif(&'static str),
+    #[error("Invalid WebP file: {0}")]
+    InvalidWebp(&'static str),
+    #[cfg(feature = "image")]
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
     #[error("IOError {0}")]
     IOError(#[from] std::io::Error),
 }
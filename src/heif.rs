@@ -0,0 +1,319 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::error::ExifError;
+use crate::jpeg::EXIF_SIGNATURE;
+
+/// Find the `Exif` item of a HEIF (e.g., HEIC) file and return it as the payload of an EXIF
+/// segment, starting with the `Exif\0\0` signature so it can be handed to libexif.
+///
+/// The item is located through the `iinf` and `iloc` boxes of the top-level `meta` box. Its
+/// data starts with the offset of the TIFF header, which is skipped.
+pub(crate) fn find_exif<R: Read + Seek>(reader: &mut R) -> Result<Vec<u8>, ExifError> {
+    let meta = read_meta(reader)?;
+
+    // meta is a full box, skip its version and flags
+    let children = meta
+        .get(4..)
+        .ok_or(ExifError::InvalidHeif("truncated meta box"))?;
+    let find = |kind: &[u8; 4]| {
+        Boxes::new(children)
+            .find(|(k, _)| k == kind)
+            .map(|(_, b)| b)
+    };
+
+    let iinf = find(b"iinf").ok_or(ExifError::InvalidHeif("missing iinf box"))?;
+    let item_id = exif_item_id(iinf).ok_or(ExifError::InvalidHeif("no Exif item"))?;
+
+    let iloc = find(b"iloc").ok_or(ExifError::InvalidHeif("missing iloc box"))?;
+    let location =
+        item_location(iloc, item_id).ok_or(ExifError::InvalidHeif("Exif item has no location"))?;
+
+    let mut item = Vec::new();
+    match location.construction_method {
+        // Offsets in the file
+        0 => {
+            for (offset, length) in location.extents {
+                reader.seek(SeekFrom::Start(offset))?;
+                if length == 0 {
+                    reader.read_to_end(&mut item)?;
+                } else {
+                    let read = reader.take(length).read_to_end(&mut item)?;
+                    if read as u64 != length {
+                        return Err(ExifError::InvalidHeif("Exif item is truncated"));
+                    }
+                }
+            }
+        }
+        // Offsets in the idat box
+        1 => {
+            let idat = find(b"idat").ok_or(ExifError::InvalidHeif("missing idat box"))?;
+            for (offset, length) in location.extents {
+                let end = if length == 0 {
+                    idat.len() as u64
+                } else {
+                    offset
+                        .checked_add(length)
+                        .ok_or(ExifError::InvalidHeif("invalid Exif item extent"))?
+                };
+                let extent = idat
+                    .get(offset as usize..end as usize)
+                    .ok_or(ExifError::InvalidHeif("Exif item is truncated"))?;
+                item.extend_from_slice(extent);
+            }
+        }
+        _ => {
+            return Err(ExifError::InvalidHeif(
+                "unsupported item construction method",
+            ))
+        }
+    }
+
+    // The item starts with the offset of the TIFF header from the end of the offset itself
+    let tiff = item
+        .get(..4)
+        .map(|offset| u32::from_be_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize)
+        .and_then(|offset| item.get(4 + offset..))
+        .filter(|tiff| tiff.starts_with(b"II") || tiff.starts_with(b"MM"))
+        .ok_or(ExifError::InvalidHeif("Exif item has no TIFF header"))?;
+
+    Ok([EXIF_SIGNATURE, tiff].concat())
+}
+
+/// Read the body of the top-level `meta` box.
+fn read_meta<R: Read + Seek>(reader: &mut R) -> Result<Vec<u8>, ExifError> {
+    loop {
+        let mut header = [0u8; 8];
+        match reader.read_exact(&mut header) {
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(ExifError::InvalidHeif("missing meta box"));
+            }
+            result => result?,
+        }
+
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let body_len = match size {
+            // The box extends to the end of the file
+            0 => None,
+            1 => {
+                let mut large_size = [0u8; 8];
+                reader.read_exact(&mut large_size)?;
+                Some(
+                    u64::from_be_bytes(large_size)
+                        .checked_sub(16)
+                        .ok_or(ExifError::InvalidHeif("invalid box size"))?,
+                )
+            }
+            size => Some(
+                size.checked_sub(8)
+                    .ok_or(ExifError::InvalidHeif("invalid box size"))?,
+            ),
+        };
+
+        if &header[4..] == b"meta" {
+            let mut meta = Vec::new();
+            match body_len {
+                Some(len) => reader.take(len).read_to_end(&mut meta)?,
+                None => reader.read_to_end(&mut meta)?,
+            };
+            return Ok(meta);
+        }
+
+        match body_len {
+            Some(len) => reader.seek(SeekFrom::Current(len as i64))?,
+            None => return Err(ExifError::InvalidHeif("missing meta box")),
+        };
+    }
+}
+
+/// ID of the item with the `Exif` type in the `iinf` box.
+fn exif_item_id(iinf: &[u8]) -> Option<u32> {
+    let mut reader = Reader::new(iinf);
+    let version = reader.u8()?;
+    reader.skip(3)?;
+    if version == 0 {
+        reader.u16()?;
+    } else {
+        reader.u32()?;
+    }
+
+    for (kind, infe) in Boxes::new(reader.rest()) {
+        if &kind != b"infe" {
+            continue;
+        }
+
+        let mut reader = Reader::new(infe);
+        let version = reader.u8()?;
+        reader.skip(3)?;
+        // Only version 2 and later have an item type
+        if version < 2 {
+            continue;
+        }
+
+        let id = if version == 2 {
+            reader.u16()? as u32
+        } else {
+            reader.u32()?
+        };
+        // item_protection_index
+        reader.skip(2)?;
+
+        if reader.bytes(4)? == b"Exif" {
+            return Some(id);
+        }
+    }
+
+    None
+}
+
+struct Location {
+    construction_method: u16,
+    /// Offset and length of each extent, a length of 0 means the rest of the data.
+    extents: Vec<(u64, u64)>,
+}
+
+/// Location of the item with the given ID in the `iloc` box.
+fn item_location(iloc: &[u8], item_id: u32) -> Option<Location> {
+    let mut reader = Reader::new(iloc);
+    let version = reader.u8()?;
+    reader.skip(3)?;
+
+    let sizes = reader.u8()?;
+    let (offset_size, length_size) = (sizes >> 4, sizes & 0xf);
+    let sizes = reader.u8()?;
+    let base_offset_size = sizes >> 4;
+    let index_size = if version == 1 || version == 2 {
+        sizes & 0xf
+    } else {
+        0
+    };
+
+    let count = if version < 2 {
+        reader.u16()? as u32
+    } else {
+        reader.u32()?
+    };
+
+    for _ in 0..count {
+        let id = if version < 2 {
+            reader.u16()? as u32
+        } else {
+            reader.u32()?
+        };
+        let construction_method = if version == 1 || version == 2 {
+            reader.u16()? & 0xf
+        } else {
+            0
+        };
+        // data_reference_index
+        reader.skip(2)?;
+        let base_offset = reader.uint(base_offset_size)?;
+
+        let extent_count = reader.u16()?;
+        let mut extents = Vec::with_capacity(extent_count as usize);
+        for _ in 0..extent_count {
+            reader.uint(index_size)?;
+            let offset = base_offset.checked_add(reader.uint(offset_size)?)?;
+            let length = reader.uint(length_size)?;
+            extents.push((offset, length));
+        }
+
+        if id == item_id {
+            return Some(Location {
+                construction_method,
+                extents,
+            });
+        }
+    }
+
+    None
+}
+
+/// Iterator over the boxes in a buffer, yielding their type and body.
+struct Boxes<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Boxes<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Boxes { buf }
+    }
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = ([u8; 4], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut reader = Reader::new(self.buf);
+        let size = reader.u32()? as u64;
+        let kind: [u8; 4] = reader.bytes(4)?.try_into().ok()?;
+
+        let (header_len, size) = match size {
+            0 => (8, self.buf.len() as u64),
+            1 => (16, reader.uint(8)?),
+            size => (8, size),
+        };
+        if size < header_len || size > self.buf.len() as u64 {
+            self.buf = &[];
+            return None;
+        }
+
+        let body = &self.buf[header_len as usize..size as usize];
+        self.buf = &self.buf[size as usize..];
+
+        Some((kind, body))
+    }
+}
+
+/// Reads big-endian integers from a buffer.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Reader { buf }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < len {
+            return None;
+        }
+
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// An integer of 0, 4 or 8 bytes, as used for the sizes in the `iloc` box.
+    fn uint(&mut self, size: u8) -> Option<u64> {
+        match size {
+            0 => Some(0),
+            4 => self.u32().map(u64::from),
+            8 => self
+                .bytes(8)
+                .map(|b| u64::from_be_bytes(b.try_into().unwrap())),
+            _ => None,
+        }
+    }
+}
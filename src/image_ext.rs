@@ -0,0 +1,24 @@
+use crate::data::Data;
+use crate::error::ExifError;
+use crate::reader::DataReader;
+
+/// Read the EXIF data from an encoded image, meant to be used alongside
+/// `image::load_from_memory` on the same buffer.
+///
+/// Data that the `image` crate doesn't recognize as an image is rejected with
+/// `ExifError::Image`. The EXIF segment of JPEGs is located directly, the other formats are
+/// handed to libexif's loader.
+///
+/// # Example
+/// ```rust
+/// let bytes = std::fs::read("assets/file.jpg").unwrap();
+///
+/// let image = image::load_from_memory(&bytes).unwrap();
+/// let exif = libexif::from_image_bytes(&bytes).unwrap();
+/// println!("{}x{}, {:?}", image.width(), image.height(), exif.byte_order());
+/// ```
+pub fn from_image_bytes(bytes: &[u8]) -> Result<Data, ExifError> {
+    image::guess_format(bytes)?;
+
+    DataReader::new().from_bytes(bytes)
+}
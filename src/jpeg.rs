@@ -0,0 +1,271 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::error::ExifError;
+use crate::reader::invalid_data;
+
+/// Signature at the start of the APP1 segment holding the EXIF data.
+pub(crate) const EXIF_SIGNATURE: &[u8] = b"Exif\0\0";
+
+const MARKER_SOI: u8 = 0xd8;
+const MARKER_EOI: u8 = 0xd9;
+const MARKER_SOS: u8 = 0xda;
+const MARKER_APP0: u8 = 0xe0;
+const MARKER_APP1: u8 = 0xe1;
+
+/// Result of scanning a JPEG for its EXIF segment.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum Scan {
+    /// The EXIF segment.
+    Found(ExifSegment),
+
+    /// The image data starts before any EXIF segment.
+    Missing,
+
+    /// More data is needed to find the EXIF segment. When the data ends within the EXIF
+    /// segment, the range its length claims for the payload is given.
+    Incomplete(Option<Range<usize>>),
+
+    /// The data doesn't start with a JPEG SOI marker.
+    NotJpeg,
+}
+
+/// Location of the EXIF segment in a JPEG.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct ExifSegment {
+    /// The payload of the segment, starting with the EXIF identifier.
+    pub payload: Range<usize>,
+
+    /// Position of the TIFF header, right after the identifier.
+    pub tiff_header: usize,
+}
+
+impl ExifSegment {
+    /// The payload with the canonical `Exif\0\0` identifier, as libexif expects it.
+    pub(crate) fn normalized<'a>(&self, buf: &'a [u8]) -> Cow<'a, [u8]> {
+        let payload = &buf[self.payload.clone()];
+        if payload.starts_with(EXIF_SIGNATURE) {
+            Cow::Borrowed(payload)
+        } else {
+            Cow::Owned([EXIF_SIGNATURE, &buf[self.tiff_header..self.payload.end]].concat())
+        }
+    }
+}
+
+/// Walk the segments before the image data and find the APP1 segment with the `Exif\0\0`
+/// signature, skipping any other APP1 segment such as XMP.
+///
+/// When there's no segment with the canonical signature, the first one with the lowercase
+/// `exif\0\0` or with a single NUL, `Exif\0`, is used instead. Some buggy writers emit those.
+pub(crate) fn find_exif_segment(buf: &[u8]) -> Scan {
+    if buf.len() < 2 {
+        return Scan::Incomplete(None);
+    }
+    if buf[0] != 0xff || buf[1] != MARKER_SOI {
+        return Scan::NotJpeg;
+    }
+
+    let mut fallback = None;
+    let mut pos = 2;
+    loop {
+        // Markers may be preceded by any number of 0xff fill bytes
+        while buf.len() > pos + 1 && buf[pos] == 0xff && buf[pos + 1] == 0xff {
+            pos += 1;
+        }
+
+        if buf.len() < pos + 2 {
+            return Scan::Incomplete(None);
+        }
+        if buf[pos] != 0xff {
+            return fallback.map_or(Scan::Missing, Scan::Found);
+        }
+
+        let marker = buf[pos + 1];
+        match marker {
+            MARKER_SOS | MARKER_EOI => return fallback.map_or(Scan::Missing, Scan::Found),
+            // Standalone markers have no length
+            0x01 | 0xd0..=0xd7 => {
+                pos += 2;
+                continue;
+            }
+            _ => {}
+        }
+
+        if buf.len() < pos + 4 {
+            return Scan::Incomplete(None);
+        }
+
+        // The length includes its own two bytes
+        let len = u16::from_be_bytes([buf[pos + 2], buf[pos + 3]]) as usize;
+        if len < 2 {
+            return fallback.map_or(Scan::Missing, Scan::Found);
+        }
+
+        let start = pos + 4;
+        let end = pos + 2 + len;
+        if end > buf.len() {
+            let available = &buf[start.min(buf.len())..];
+            let exif =
+                EXIF_SIGNATURE.starts_with(available) || available.starts_with(EXIF_SIGNATURE);
+
+            return Scan::Incomplete(Some(start..end).filter(|_| marker == MARKER_APP1 && exif));
+        }
+
+        if marker == MARKER_APP1 {
+            let payload = &buf[start..end];
+            if payload.starts_with(EXIF_SIGNATURE) {
+                return Scan::Found(ExifSegment {
+                    payload: start..end,
+                    tiff_header: start + EXIF_SIGNATURE.len(),
+                });
+            }
+
+            if let Some(len) = tolerant_identifier_len(payload).filter(|_| fallback.is_none()) {
+                fallback = Some(ExifSegment {
+                    payload: start..end,
+                    tiff_header: start + len,
+                });
+            }
+        }
+
+        pos = end;
+    }
+}
+
+/// Return the byte range of the APP1 segment holding the EXIF data of a JPEG, from its
+/// `0xFFE1` marker to the end of the segment, without parsing the EXIF data.
+///
+/// The segments are found like [`Data::from_bytes()`](struct.Data.html#method.from_bytes)
+/// does. `None` is returned when the buffer isn't a JPEG, has no EXIF segment or ends within
+/// it.
+///
+/// # Example
+/// ```rust
+/// let jpeg = std::fs::read("assets/file.jpg").unwrap();
+/// let segment = libexif::find_app1_exif(&jpeg).unwrap();
+/// assert_eq!(segment, 2..272);
+///
+/// // Splice the EXIF data out
+/// let stripped = [&jpeg[..segment.start], &jpeg[segment.end..]].concat();
+/// assert_eq!(libexif::find_app1_exif(&stripped), None);
+/// ```
+pub fn find_app1_exif(buf: &[u8]) -> Option<Range<usize>> {
+    match find_exif_segment(buf) {
+        // The payload follows the marker and the length
+        Scan::Found(segment) => Some(segment.payload.start - 4..segment.payload.end),
+        _ => None,
+    }
+}
+
+/// Find the JPEG streams embedded in some data, from an SOI marker to the first EOI marker
+/// after it, e.g., the previews some maker notes hold.
+pub(crate) fn embedded_jpegs(buf: &[u8]) -> Vec<&[u8]> {
+    let mut jpegs = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = find(&buf[pos..], &[0xff, MARKER_SOI, 0xff]).map(|i| pos + i) {
+        let end = match find(&buf[start..], &[0xff, MARKER_EOI]) {
+            Some(end) => start + end + 2,
+            None => break,
+        };
+
+        jpegs.push(&buf[start..end]);
+        pos = end;
+    }
+
+    jpegs
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// The error for data that ended before the EXIF segment was found, `len` being the length
+/// of the data.
+pub(crate) fn incomplete_error(len: usize, segment: Option<Range<usize>>) -> ExifError {
+    match segment {
+        Some(segment) => ExifError::TruncatedExif {
+            expected: segment.len(),
+            available: len.saturating_sub(segment.start),
+        },
+        None => invalid_data(),
+    }
+}
+
+/// Length of a misspelled EXIF identifier at the start of an APP1 payload, either the
+/// lowercase `exif\0\0` or `Exif\0` with a single NUL directly followed by the TIFF header.
+fn tolerant_identifier_len(payload: &[u8]) -> Option<usize> {
+    if payload.len() < 6 || !payload[..4].eq_ignore_ascii_case(b"exif") || payload[4] != 0 {
+        return None;
+    }
+
+    if payload[5] == 0 {
+        Some(6)
+    } else if payload[5..].starts_with(b"II") || payload[5..].starts_with(b"MM") {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+/// Position where a new EXIF segment goes in a JPEG without one: right after the SOI marker,
+/// or after the APP0 segment following it since JFIF requires that one to come first.
+pub(crate) fn exif_insert_position(buf: &[u8]) -> usize {
+    if buf.get(2..4) == Some(&[0xff, MARKER_APP0]) {
+        if let Some(len) = buf
+            .get(4..6)
+            .map(|len| u16::from_be_bytes([len[0], len[1]]))
+        {
+            let end = 4 + len as usize;
+            if len >= 2 && end <= buf.len() {
+                return end;
+            }
+        }
+    }
+
+    2
+}
+
+/// Check the structure of a JPEG written with a new EXIF segment of `exif_len` bytes, whose
+/// marker is at `exif_start`.
+///
+/// The JPEG has to start with SOI and the length of the EXIF segment must match the EXIF
+/// data. The rest of the file has to start with a marker and contain an EOI.
+pub(crate) fn validate(jpeg: &[u8], exif_start: usize, exif_len: usize) -> Result<(), ExifError> {
+    if !jpeg.starts_with(&[0xff, MARKER_SOI]) {
+        return Err(ExifError::MalformedOutput("missing SOI marker"));
+    }
+
+    let segment = &jpeg[exif_start.min(jpeg.len())..];
+    if segment.len() < 4 || segment[..2] != [0xff, MARKER_APP1] {
+        return Err(ExifError::MalformedOutput("missing APP1 marker"));
+    }
+
+    let len = u16::from_be_bytes([segment[2], segment[3]]) as usize;
+    if len != exif_len + 2 {
+        return Err(ExifError::MalformedOutput(
+            "APP1 length doesn't match the EXIF data",
+        ));
+    }
+
+    let rest = segment
+        .get(2 + len..)
+        .ok_or(ExifError::MalformedOutput("APP1 segment is truncated"))?;
+    if !segment[4..].starts_with(EXIF_SIGNATURE) {
+        return Err(ExifError::MalformedOutput(
+            "APP1 segment has no EXIF signature",
+        ));
+    }
+    if rest.first() != Some(&0xff) {
+        return Err(ExifError::MalformedOutput(
+            "no marker after the APP1 segment",
+        ));
+    }
+    if !rest.windows(2).any(|marker| marker == [0xff, MARKER_EOI]) {
+        return Err(ExifError::MalformedOutput("missing EOI marker"));
+    }
+
+    Ok(())
+}
@@ -29,22 +29,53 @@ extern crate paste;
 pub use bits::*;
 pub use content::*;
 pub use data::*;
+pub use diff::{MergePolicy, TagChange};
 pub use entry::*;
 pub use error::*;
+#[cfg(feature = "image")]
+pub use image_ext::from_image_bytes;
+pub use jpeg::find_app1_exif;
+pub use mem::Allocator;
+pub use mnote::*;
+pub use reader::*;
+pub use semantics::*;
 pub use tag::*;
+pub use tiff::{EntryOffsetInfo, IfdOffsetInfo};
+pub use tree::*;
 pub use value::*;
 
 mod internal;
 
 // pub mod bindings; // Just in case someone wants access to the raw bindings
 mod bindings;
-pub use bindings::{ExifEntry, ExifIfd, ExifTag};
+pub use bindings::{
+    ExifEntry, ExifIfd, ExifLong, ExifMemAllocFunc, ExifMemFreeFunc, ExifMemReallocFunc, ExifTag,
+};
 pub mod error;
 
 mod bits;
 mod content;
 mod data;
+#[cfg(feature = "chrono")]
+mod datetime;
+mod diff;
 mod entry;
+mod heif;
+#[cfg(feature = "image")]
+mod image_ext;
+mod jpeg;
 mod loader;
+mod log;
+mod mem;
+mod mnote;
+pub mod prelude;
+mod reader;
+mod semantics;
 mod tag;
+mod tiff;
+mod tree;
 mod value;
+mod webp;
+#[cfg(feature = "xml")]
+mod xml;
+mod xp;
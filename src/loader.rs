@@ -1,9 +1,6 @@
 use crate::bindings::*;
 use libc::c_uint;
 
-use crate::data::Data;
-use crate::internal::*;
-
 pub struct Loader {
     inner: *mut ExifLoader,
 }
@@ -25,14 +22,17 @@ impl Loader {
         Loader { inner: ptr }
     }
 
-    pub fn data(&self) -> Option<Data> {
-        let ptr = unsafe { exif_loader_get_data(self.inner) };
+    /// The EXIF data found by the loader, not parsed yet.
+    pub fn buf(&self) -> Option<&[u8]> {
+        let mut buf: *const u8 = std::ptr::null();
+        let mut size: c_uint = 0;
+        unsafe { exif_loader_get_buf(self.inner, &mut buf, &mut size) };
 
-        if !ptr.is_null() {
-            Some(Data::from_libexif(ptr))
-        } else {
-            None
+        if buf.is_null() || size == 0 {
+            return None;
         }
+
+        Some(unsafe { std::slice::from_raw_parts(buf, size as usize) })
     }
 
     pub fn write_data(&mut self, data: &mut [u8]) -> bool {
@@ -0,0 +1,36 @@
+#include <stdarg.h>
+#include <stdio.h>
+
+#include <libexif/exif-log.h>
+
+/*
+ * libexif passes the arguments of its log messages as a va_list, whose type differs between
+ * platforms, so the messages are formatted here and handed to Rust as plain strings.
+ */
+
+typedef void (*ExifRsLogCallback)(void *data, ExifLogCode code, const char *domain,
+                                  const char *message);
+
+typedef struct {
+    ExifRsLogCallback callback;
+    void *data;
+} ExifRsLogCollector;
+
+static void exif_rs_log_func(ExifLog *log, ExifLogCode code, const char *domain,
+                             const char *format, va_list args, void *data)
+{
+    ExifRsLogCollector *collector = data;
+    char message[512];
+
+    (void) log;
+    if (!format)
+        return;
+
+    vsnprintf(message, sizeof(message), format, args);
+    collector->callback(collector->data, code, domain, message);
+}
+
+void exif_rs_log_set_collector(ExifLog *log, ExifRsLogCollector *collector)
+{
+    exif_log_set_func(log, exif_rs_log_func, collector);
+}
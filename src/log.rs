@@ -0,0 +1,101 @@
+use std::ffi::CStr;
+
+use libc::{c_char, c_void};
+
+use crate::bindings::*;
+use crate::error::ExifError;
+
+/// Callback the messages are handed to once formatted, with the data it's given, see
+/// `src/log.c`. The `va_list` libexif passes can't be named portably from Rust.
+#[repr(C)]
+struct Collector {
+    callback: unsafe extern "C" fn(*mut c_void, ExifLogCode, *const c_char, *const c_char),
+    data: *mut c_void,
+}
+
+extern "C" {
+    fn exif_rs_log_set_collector(log: *mut ExifLog, collector: *mut Collector);
+}
+
+/// Collects the messages libexif logs, apart from the debug ones, while it parses some data.
+pub(crate) struct WarningLog {
+    inner: *mut ExifLog,
+    messages: *mut Vec<String>,
+    collector: *mut Collector,
+}
+
+impl WarningLog {
+    pub(crate) fn new() -> Result<Self, ExifError> {
+        let ptr = unsafe { exif_log_new() };
+        if ptr.is_null() {
+            return Err(ExifError::MemNewFail);
+        }
+
+        let messages = Box::into_raw(Box::<Vec<String>>::default());
+        let collector = Box::into_raw(Box::new(Collector {
+            callback: collect,
+            data: messages as *mut c_void,
+        }));
+        unsafe { exif_rs_log_set_collector(ptr, collector) };
+
+        Ok(WarningLog {
+            inner: ptr,
+            messages,
+            collector,
+        })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut ExifLog {
+        self.inner
+    }
+
+    /// Record a message of the crate's own along with libexif's.
+    pub(crate) fn push(&self, message: String) {
+        unsafe { (*self.messages).push(message) };
+    }
+
+    /// Stop collecting and return the messages logged so far.
+    pub(crate) fn into_messages(self) -> Vec<String> {
+        self.detach();
+        unsafe { std::mem::take(&mut *self.messages) }
+    }
+
+    /// Stop calling back into the collector, the data may hold on to the log after it's gone.
+    fn detach(&self) {
+        unsafe { exif_log_set_func(self.inner, None, std::ptr::null_mut()) };
+    }
+}
+
+impl Drop for WarningLog {
+    fn drop(&mut self) {
+        self.detach();
+        unsafe {
+            drop(Box::from_raw(self.collector));
+            drop(Box::from_raw(self.messages));
+            exif_log_unref(self.inner);
+        }
+    }
+}
+
+unsafe extern "C" fn collect(
+    data: *mut c_void,
+    code: ExifLogCode,
+    domain: *const c_char,
+    message: *const c_char,
+) {
+    if code == ExifLogCode::EXIF_LOG_CODE_DEBUG {
+        return;
+    }
+
+    let message = CStr::from_ptr(message).to_string_lossy();
+    let messages = &mut *(data as *mut Vec<String>);
+    if domain.is_null() {
+        messages.push(message.into_owned());
+    } else {
+        messages.push(format!(
+            "{}: {}",
+            CStr::from_ptr(domain).to_string_lossy(),
+            message
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::bindings::*;
+use crate::error::ExifError;
+
+/// Memory allocator libexif uses for EXIF data, wrapping an `ExifMem`.
+///
+/// The default allocator is libexif's, built on `calloc`, `realloc` and `free`. A custom one can
+/// be given to [`Data::new_with_allocator()`](struct.Data.html#method.new_with_allocator) or
+/// [`DataReader::with_allocator()`](struct.DataReader.html#method.with_allocator), e.g., to
+/// track or cap libexif's allocations.
+pub struct Allocator {
+    inner: *mut ExifMem,
+}
+
+impl Allocator {
+    /// Create an allocator from C allocation functions.
+    ///
+    /// # Safety
+    /// The functions must behave like `calloc`, `realloc` and `free`: the memory returned by
+    /// `alloc` is zeroed, and memory from either `alloc` or `realloc` is released by `free`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use libexif::{Allocator, Data, ExifLong};
+    ///
+    /// static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// unsafe extern "C" fn alloc(size: ExifLong) -> *mut libc::c_void {
+    ///     ALLOCATED.fetch_add(size as usize, Ordering::Relaxed);
+    ///     libc::calloc(size as usize, 1)
+    /// }
+    ///
+    /// unsafe extern "C" fn realloc(ptr: *mut libc::c_void, size: ExifLong) -> *mut libc::c_void {
+    ///     libc::realloc(ptr, size as usize)
+    /// }
+    ///
+    /// unsafe extern "C" fn free(ptr: *mut libc::c_void) {
+    ///     libc::free(ptr)
+    /// }
+    ///
+    /// let allocator = unsafe { Allocator::new(Some(alloc), Some(realloc), Some(free)) }.unwrap();
+    /// let data = Data::new_with_allocator(allocator).unwrap();
+    /// assert!(ALLOCATED.load(Ordering::Relaxed) > 0);
+    /// ```
+    pub unsafe fn new(
+        alloc: ExifMemAllocFunc,
+        realloc: ExifMemReallocFunc,
+        free: ExifMemFreeFunc,
+    ) -> Result<Self, ExifError> {
+        let ptr = exif_mem_new(alloc, realloc, free);
+        if ptr.is_null() {
+            return Err(ExifError::MemNewFail);
+        }
+
+        Ok(Allocator { inner: ptr })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut ExifMem {
+        self.inner
+    }
+}
+
+impl Default for Allocator {
+    fn default() -> Self {
+        let ptr = unsafe { exif_mem_new_default() };
+
+        assert!(!ptr.is_null());
+
+        Allocator { inner: ptr }
+    }
+}
+
+/// The copy refers to the same `ExifMem`.
+impl Clone for Allocator {
+    fn clone(&self) -> Self {
+        unsafe { exif_mem_ref(self.inner) };
+
+        Allocator { inner: self.inner }
+    }
+}
+
+impl Drop for Allocator {
+    fn drop(&mut self) {
+        unsafe { exif_mem_unref(self.inner) };
+    }
+}
+
+impl Debug for Allocator {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Allocator")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
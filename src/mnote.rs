@@ -0,0 +1,218 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+
+use libc::{c_char, c_uint};
+
+use crate::bindings::*;
+use crate::bits::IFD;
+use crate::data::Data;
+
+/// Maker of the camera, which decides how the maker note is laid out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Vendor {
+    /// Canon.
+    Canon,
+
+    /// Nikon.
+    Nikon,
+
+    /// Olympus, or OM Digital Solutions.
+    Olympus,
+
+    /// Fujifilm.
+    Fujifilm,
+
+    /// Pentax, or Ricoh.
+    Pentax,
+
+    /// Apple.
+    Apple,
+
+    /// Any other maker, or a missing `Make` tag.
+    Other,
+}
+
+impl Vendor {
+    /// Detect the vendor from the value of the `Make` tag.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Vendor;
+    /// assert_eq!(Vendor::from_make("Canon"), Vendor::Canon);
+    /// assert_eq!(Vendor::from_make("NIKON CORPORATION"), Vendor::Nikon);
+    /// assert_eq!(Vendor::from_make("OM Digital Solutions"), Vendor::Olympus);
+    /// assert_eq!(Vendor::from_make("Leica"), Vendor::Other);
+    /// ```
+    pub fn from_make(make: &str) -> Self {
+        let make = make.trim().to_ascii_lowercase();
+        let prefixes = [
+            ("canon", Vendor::Canon),
+            ("nikon", Vendor::Nikon),
+            ("olympus", Vendor::Olympus),
+            ("om digital", Vendor::Olympus),
+            ("fujifilm", Vendor::Fujifilm),
+            ("pentax", Vendor::Pentax),
+            ("asahi", Vendor::Pentax),
+            ("ricoh", Vendor::Pentax),
+            ("apple", Vendor::Apple),
+        ];
+
+        prefixes
+            .into_iter()
+            .find(|(prefix, _)| make.starts_with(prefix))
+            .map_or(Vendor::Other, |(_, vendor)| vendor)
+    }
+}
+
+/// An entry of the maker note, as interpreted by libexif.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MakerNoteEntry {
+    /// The vendor specific ID of the entry.
+    pub id: u32,
+
+    /// The name libexif gives to the entry.
+    pub name: Option<String>,
+
+    /// Textual representation of the entry's value, as formatted by libexif.
+    pub value: Option<String>,
+}
+
+/// The maker note of some EXIF data, as returned by
+/// [`Data::maker_note()`](struct.Data.html#method.maker_note).
+///
+/// libexif interprets the maker notes of Canon, Nikon, Olympus, Fujifilm, Pentax and Apple
+/// cameras. Typed accessors for the most common fields are available behind the vendor
+/// features, e.g., `canon` and `nikon`.
+#[derive(Debug)]
+pub struct MakerNote<'a> {
+    inner: *mut ExifMnoteData,
+    vendor: Vendor,
+    data: PhantomData<&'a Data>,
+}
+
+impl<'a> MakerNote<'a> {
+    /// The vendor of the camera, detected from the `Make` tag.
+    pub fn vendor(&self) -> Vendor {
+        self.vendor
+    }
+
+    /// Number of entries in the maker note.
+    pub fn len(&self) -> usize {
+        unsafe { exif_mnote_data_count(self.inner) as usize }
+    }
+
+    /// Whether the maker note has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The entries of the maker note.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// if let Some(maker_note) = data.maker_note() {
+    ///     for entry in maker_note.entries() {
+    ///         println!("{:?} = {:?}", entry.name, entry.value);
+    ///     }
+    /// }
+    /// ```
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = MakerNoteEntry> + '_ {
+        (0..self.len() as c_uint).map(move |index| MakerNoteEntry {
+            id: unsafe { exif_mnote_data_get_id(self.inner, index) },
+            name: c_string(unsafe { exif_mnote_data_get_name(self.inner, index) }),
+            value: self.value(index),
+        })
+    }
+
+    /// The value of the first entry with the given ID.
+    #[cfg(any(feature = "canon", feature = "nikon"))]
+    fn value_of(&self, id: u32) -> Option<String> {
+        (0..self.len() as c_uint)
+            .find(|&index| unsafe { exif_mnote_data_get_id(self.inner, index) } == id)
+            .and_then(|index| self.value(index))
+    }
+
+    fn value(&self, index: c_uint) -> Option<String> {
+        let mut buffer = [0 as c_char; 1024];
+        let ptr = unsafe {
+            exif_mnote_data_get_value(
+                self.inner,
+                index,
+                buffer.as_mut_ptr(),
+                buffer.len() as c_uint,
+            )
+        };
+
+        c_string(ptr)
+    }
+
+    /// The model of the lens, from the `LensModel` entry (`0x0095`) recent Canon cameras write.
+    ///
+    /// Returns `None` for other vendors.
+    #[cfg(feature = "canon")]
+    pub fn canon_lens_model(&self) -> Option<String> {
+        if self.vendor != Vendor::Canon {
+            return None;
+        }
+
+        let model = self.value_of(0x0095)?;
+        let model = model.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+
+        Some(model.to_string()).filter(|model| !model.is_empty())
+    }
+
+    /// The ISO the image was taken with, from the `ISO` entry (`0x0002`).
+    ///
+    /// Returns `None` for other vendors.
+    #[cfg(feature = "nikon")]
+    pub fn nikon_iso(&self) -> Option<u32> {
+        if self.vendor != Vendor::Nikon {
+            return None;
+        }
+
+        // The value is formatted as, e.g., "ISO 200"
+        let value = self.value_of(0x0002)?;
+        let iso = value
+            .rsplit(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty())?;
+
+        iso.parse().ok().filter(|&iso| iso != 0)
+    }
+}
+
+impl Data {
+    /// Returns the maker note, when libexif could interpret it.
+    pub fn maker_note(&self) -> Option<MakerNote> {
+        let ptr = unsafe { exif_data_get_mnote_data(self.as_ptr()) };
+        if ptr.is_null() {
+            return None;
+        }
+
+        let vendor = self
+            .get_entry(IFD::Image, ExifTag::EXIF_TAG_MAKE)
+            .ok()
+            .and_then(|entry| entry.value_auto().ok())
+            .and_then(|value| value.text_trimmed())
+            .map_or(Vendor::Other, |make| Vendor::from_make(&make));
+
+        Some(MakerNote {
+            inner: ptr,
+            vendor,
+            data: PhantomData,
+        })
+    }
+}
+
+/// Copy a string returned by libexif, which is null when there's none.
+fn c_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    Some(
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
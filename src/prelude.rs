@@ -0,0 +1,24 @@
+//! Re-exports the commonly used types so they can be imported with a single glob import.
+//!
+//! ```rust
+//! use libexif::prelude::*;
+//!
+//! let mut data = Data::new();
+//! let order = data.byte_order();
+//! data.set_entry(
+//!     IFD::Image,
+//!     ExifTag::EXIF_TAG_ARTIST,
+//!     Value::Text("Jane Doe".to_string()),
+//!     order,
+//! )
+//! .unwrap();
+//! ```
+
+pub use crate::bits::{ByteOrder, DataType, IFD};
+pub use crate::content::Content;
+pub use crate::data::Data;
+pub use crate::entry::Entry;
+pub use crate::error::ExifError;
+pub use crate::tag::Tag;
+pub use crate::value::{Rational, Value};
+pub use crate::ExifTag;
@@ -0,0 +1,468 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::bindings::*;
+use crate::bits::{DataOption, IFD};
+use crate::data::Data;
+use crate::error::ExifError;
+use crate::heif;
+use crate::internal::*;
+use crate::jpeg::{self, ExifSegment, Scan};
+use crate::loader::Loader;
+use crate::log::WarningLog;
+use crate::mem::Allocator;
+use crate::tiff::Tiff;
+use crate::webp;
+
+/// Number of bytes read from a file at a time while looking for the EXIF data.
+const CHUNK_SIZE: usize = 4096;
+
+/// Tags the maker note may be given while libexif parses the data when maker notes aren't
+/// parsed, libexif only interprets the entry tagged `MakerNote`. The first one the EXIF IFD
+/// doesn't have is used.
+///
+/// These are `UNDEFINED` tags of any length libexif knows in the EXIF IFD, so it keeps the
+/// entry as it is even when unknown tags are ignored.
+const MAKER_NOTE_PLACEHOLDERS: [ExifTag; 4] = [
+    ExifTag::EXIF_TAG_DEVICE_SETTING_DESCRIPTION,
+    ExifTag::EXIF_TAG_SPATIAL_FREQUENCY_RESPONSE,
+    ExifTag::EXIF_TAG_OECF,
+    ExifTag::EXIF_TAG_CFA_PATTERN,
+];
+
+/// Character set used to decode the text of `ASCII` tags.
+///
+/// The EXIF specification only allows 7-bit ASCII in these tags but some older cameras store
+/// Latin-1 or Shift-JIS text in them anyway.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Charset {
+    /// UTF-8, invalid sequences are replaced with `U+FFFD`.
+    #[default]
+    Utf8,
+
+    /// ISO-8859-1, every byte is mapped to the code point with the same value.
+    Latin1,
+
+    /// Shift-JIS, used by some older Japanese cameras.
+    #[cfg(feature = "shift_jis")]
+    ShiftJis,
+}
+
+impl Charset {
+    pub(crate) fn decode(&self, bytes: &[u8]) -> String {
+        match *self {
+            Charset::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Charset::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+            #[cfg(feature = "shift_jis")]
+            Charset::ShiftJis => encoding_rs::SHIFT_JIS.decode(bytes).0.into_owned(),
+        }
+    }
+}
+
+/// Statistics about reading a file, see
+/// [`DataReader::open_with_stats()`](struct.DataReader.html#method.open_with_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseStats {
+    /// Number of bytes read from the file, only the start of a JPEG is read.
+    pub bytes_read: usize,
+
+    /// Number of chunks the file was read in.
+    pub chunks: usize,
+
+    /// Number of entries in all the IFDs of the parsed data.
+    pub entries: usize,
+
+    /// Time spent opening, reading and parsing the file.
+    pub elapsed: Duration,
+}
+
+/// Reads EXIF data with non-default settings.
+///
+/// [`Data::open()`](struct.Data.html#method.open) is the same as reading with a default
+/// `DataReader`.
+///
+/// # Example
+/// ```rust
+/// use libexif::{Charset, DataReader};
+/// let data = DataReader::new()
+///     .with_charset(Charset::Latin1)
+///     .open("assets/file.jpg")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DataReader {
+    charset: Charset,
+    disabled_options: Vec<DataOption>,
+    skip_maker_notes: bool,
+    allocator: Option<Allocator>,
+}
+
+impl DataReader {
+    /// Create a reader with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the character set used to decode the text of `ASCII` tags.
+    pub fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Disable a data processing option while the data is parsed.
+    ///
+    /// Disabling `FollowSpecification` keeps libexif from fixing the data as it is loaded, see
+    /// [`Data::write_preserving()`](struct.Data.html#method.write_preserving).
+    pub fn without_option(mut self, option: DataOption) -> Self {
+        if !self.disabled_options.contains(&option) {
+            self.disabled_options.push(option);
+        }
+        self
+    }
+
+    /// Use the given allocator for the memory of the data that is read, see
+    /// [`Data::new_with_allocator()`](struct.Data.html#method.new_with_allocator).
+    pub fn with_allocator(mut self, allocator: Allocator) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Set whether libexif interprets the vendor specific maker note, which is the default.
+    ///
+    /// Parsing the maker notes of unusual files is slow and error prone, disabling it is safer
+    /// for untrusted files. The maker note is then kept as an opaque `MakerNote` entry of the EXIF
+    /// IFD.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataReader, ExifTag, IFD};
+    /// let data = DataReader::new()
+    ///     .parse_maker_notes(false)
+    ///     .open("assets/file.jpg")
+    ///     .unwrap();
+    /// assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// ```
+    pub fn parse_maker_notes(mut self, parse: bool) -> Self {
+        self.skip_maker_notes = !parse;
+        self
+    }
+
+    /// Read the EXIF data from a JPEG file.
+    ///
+    /// The segments of the JPEG are read until the APP1 segment with the `Exif\0\0` signature
+    /// is found, other APP1 segments such as XMP are skipped. Files that aren't JPEGs are handed
+    /// to libexif's loader.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<Data, ExifError> {
+        self.open_with_log(path, None)
+    }
+
+    /// Same as [`open()`](#method.open), with libexif logging to the given log while it parses
+    /// the data.
+    pub(crate) fn open_with_log<P: AsRef<Path>>(
+        &self,
+        path: P,
+        log: Option<&WarningLog>,
+    ) -> Result<Data, ExifError> {
+        self.read(File::open(path)?, &mut Vec::new(), log, |_| {})
+    }
+
+    /// Read the EXIF data from a reader, calling `progress` with the number of bytes read so
+    /// far after each chunk.
+    ///
+    /// Like [`open()`](#method.open) the reader is only read until the EXIF data is found, so
+    /// the last call tells how much of a large file had to be read.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::fs::File;
+    /// use libexif::DataReader;
+    /// let file = File::open("assets/file.jpg").unwrap();
+    /// let mut read = 0;
+    /// let data = DataReader::new()
+    ///     .from_reader_with_progress(file, |bytes| read = bytes)
+    ///     .unwrap();
+    /// assert!(read > 0);
+    /// ```
+    pub fn from_reader_with_progress<R: Read>(
+        &self,
+        reader: R,
+        progress: impl FnMut(usize),
+    ) -> Result<Data, ExifError> {
+        self.read(reader, &mut Vec::new(), None, progress)
+    }
+
+    /// Same as [`open()`](#method.open), also returning how much was read, how many entries
+    /// were found and how long it took, e.g., to find the files slowing down a batch job.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::DataReader;
+    /// let (data, stats) = DataReader::new().open_with_stats("assets/file.jpg").unwrap();
+    /// assert_eq!(stats.chunks, 1);
+    /// assert!(stats.bytes_read >= 272);
+    /// assert_eq!(stats.entries, data.contents().map(|content| content.len()).sum::<usize>());
+    /// ```
+    pub fn open_with_stats<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(Data, ParseStats), ExifError> {
+        let start = Instant::now();
+        let mut stats = ParseStats::default();
+
+        let data = self.read(File::open(path)?, &mut Vec::new(), None, |bytes| {
+            stats.bytes_read = bytes;
+            stats.chunks += 1;
+        })?;
+        stats.entries = data.contents().map(|content| content.len()).sum();
+        stats.elapsed = start.elapsed();
+
+        Ok((data, stats))
+    }
+
+    /// Same as [`open()`](#method.open) but the file is read into `buffer`, which is cleared
+    /// first, instead of a buffer allocated for each file, e.g., to reuse pooled buffers.
+    ///
+    /// The data doesn't borrow the buffer, libexif copies what it keeps, so the buffer can be
+    /// used for the next file right away.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataReader, ExifTag, IFD};
+    /// let reader = DataReader::new();
+    /// let mut buffer = Vec::with_capacity(64 * 1024);
+    /// for path in ["assets/file.jpg", "assets/file.jpg"] {
+    ///     let data = reader.open_using_buffer(path, &mut buffer).unwrap();
+    ///     assert!(data.get_entry(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION).is_ok());
+    /// }
+    /// ```
+    pub fn open_using_buffer<P: AsRef<Path>>(
+        &self,
+        path: P,
+        buffer: &mut Vec<u8>,
+    ) -> Result<Data, ExifError> {
+        self.read(File::open(path)?, buffer, None, |_| {})
+    }
+
+    /// Read chunks into `buffer` until the EXIF segment of a JPEG is found, or hand the data to
+    /// libexif's loader when it isn't a JPEG.
+    fn read<R: Read>(
+        &self,
+        mut reader: R,
+        buffer: &mut Vec<u8>,
+        log: Option<&WarningLog>,
+        mut progress: impl FnMut(usize),
+    ) -> Result<Data, ExifError> {
+        buffer.clear();
+        buffer.reserve(CHUNK_SIZE);
+
+        loop {
+            let len = (&mut reader).take(CHUNK_SIZE as u64).read_to_end(buffer)?;
+            if len > 0 {
+                progress(buffer.len());
+            }
+
+            match jpeg::find_exif_segment(buffer) {
+                Scan::Found(segment) => return self.load_segment(buffer, segment, log),
+                Scan::Incomplete(_) if len > 0 => continue,
+                Scan::Incomplete(segment) => {
+                    return Err(jpeg::incomplete_error(buffer.len(), segment))
+                }
+                Scan::Missing => return Err(invalid_data()),
+                Scan::NotJpeg => return self.load_with_loader(buffer, reader, log, progress),
+            }
+        }
+    }
+
+    /// Same as [`open()`](#method.open) but the whole file is read and kept in the returned
+    /// data, see [`Data::write_back()`](struct.Data.html#method.write_back).
+    pub fn open_retaining<P: AsRef<Path>>(&self, path: P) -> Result<Data, ExifError> {
+        let source = std::fs::read(path)?;
+        let data = self.from_bytes(&source)?;
+
+        Ok(data.with_source(source))
+    }
+
+    /// Read the EXIF data from a HEIF file, e.g., the HEIC photos taken by iPhones.
+    ///
+    /// The EXIF data is stored in an `Exif` item of the file's `meta` box.
+    pub fn open_heif<P: AsRef<Path>>(&self, path: P) -> Result<Data, ExifError> {
+        let mut file = BufReader::new(File::open(path)?);
+        let exif = heif::find_exif(&mut file)?;
+
+        self.load(&exif, None)
+    }
+
+    /// Read the EXIF data from a WebP file, found in the `EXIF` chunk of its RIFF container.
+    pub fn open_webp<P: AsRef<Path>>(&self, path: P) -> Result<Data, ExifError> {
+        self.from_webp_bytes(&std::fs::read(path)?)
+    }
+
+    /// Read the EXIF data from the contents of a WebP file, see
+    /// [`open_webp()`](#method.open_webp).
+    pub fn from_webp_bytes(&self, bytes: &[u8]) -> Result<Data, ExifError> {
+        let exif = webp::find_exif(bytes)?;
+
+        self.load(&exif, None)
+    }
+
+    /// Read the EXIF data from a bare TIFF block, as returned by
+    /// [`Data::raw_exif_block()`](struct.Data.html#method.raw_exif_block).
+    pub fn from_raw_exif_block(&self, tiff: &[u8]) -> Result<Data, ExifError> {
+        self.load(&[jpeg::EXIF_SIGNATURE, tiff].concat(), None)
+    }
+
+    /// Read the EXIF data from the contents of a JPEG file, see [`open()`](#method.open).
+    ///
+    /// A JPEG that ends within its EXIF segment fails with `TruncatedExif`, other JPEGs without
+    /// EXIF data fail with an `InvalidData` I/O error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataReader, ExifError};
+    /// let jpeg = std::fs::read("assets/file.jpg").unwrap();
+    /// let result = DataReader::new().from_bytes(&jpeg[..100]);
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(ExifError::TruncatedExif { expected: 266, available: 94 })
+    /// ));
+    /// ```
+    pub fn from_bytes(&self, bytes: &[u8]) -> Result<Data, ExifError> {
+        match jpeg::find_exif_segment(bytes) {
+            Scan::Found(segment) => self.load_segment(bytes, segment, None),
+            Scan::Incomplete(segment) => Err(jpeg::incomplete_error(bytes.len(), segment)),
+            Scan::Missing => Err(invalid_data()),
+            Scan::NotJpeg => self.load_with_loader(&mut bytes.to_vec(), io::empty(), None, |_| {}),
+        }
+    }
+
+    /// Parse the EXIF segment found in a JPEG.
+    fn load_segment(
+        &self,
+        jpeg: &[u8],
+        segment: ExifSegment,
+        log: Option<&WarningLog>,
+    ) -> Result<Data, ExifError> {
+        if let Some(log) = log {
+            check_segment_length(jpeg, &segment, log);
+        }
+
+        let data = self.load(&segment.normalized(jpeg), log)?;
+
+        Ok(data.with_tiff_header_offset(Some(segment.tiff_header)))
+    }
+
+    /// Parse the payload of an EXIF segment, starting with the `Exif\0\0` signature.
+    fn load(&self, exif: &[u8], log: Option<&WarningLog>) -> Result<Data, ExifError> {
+        let hidden = match self.skip_maker_notes {
+            true => hide_maker_note(exif),
+            false => None,
+        };
+        let exif = hidden.as_ref().map_or(exif, |(exif, _)| exif);
+
+        let allocator = self.allocator.clone().unwrap_or_default();
+        let ptr = unsafe { exif_data_new_mem(allocator.as_ptr()) };
+        if ptr.is_null() {
+            return Err(ExifError::ExifDataNull);
+        }
+
+        let mut data = Data::from_libexif(ptr).with_allocator(allocator);
+        for option in &self.disabled_options {
+            data.unset_option(*option);
+        }
+        if let Some(log) = log {
+            unsafe { exif_data_log(ptr, log.as_ptr()) };
+        }
+        data.load_data(exif);
+
+        if let Some((_, placeholder)) = hidden {
+            if let Ok(entry) = data.get_entry_raw(IFD::EXIF, placeholder) {
+                entry.tag = ExifTag::EXIF_TAG_MAKER_NOTE;
+            }
+        }
+
+        Ok(data.with_charset(self.charset))
+    }
+
+    /// Let libexif's loader find the EXIF data, used for the formats other than JPEG it knows.
+    fn load_with_loader(
+        &self,
+        buffer: &mut Vec<u8>,
+        mut rest: impl Read,
+        log: Option<&WarningLog>,
+        mut progress: impl FnMut(usize),
+    ) -> Result<Data, ExifError> {
+        let mut loader = Loader::new();
+        let mut read = buffer.len();
+
+        loop {
+            if buffer.is_empty() || !loader.write_data(buffer) {
+                break;
+            }
+
+            buffer.clear();
+            (&mut rest).take(CHUNK_SIZE as u64).read_to_end(buffer)?;
+            if !buffer.is_empty() {
+                read += buffer.len();
+                progress(read);
+            }
+        }
+
+        self.load(loader.buf().ok_or_else(invalid_data)?, log)
+    }
+}
+
+/// Keep libexif from interpreting the maker note of EXIF data starting with the `Exif\0\0`
+/// signature.
+///
+/// The maker note is renamed to one of the [`MAKER_NOTE_PLACEHOLDERS`] the EXIF IFD doesn't
+/// have. Returns the patched data and the placeholder, or `None` when there's no maker note or
+/// every placeholder is taken, in which case libexif interprets the maker note after all.
+fn hide_maker_note(exif: &[u8]) -> Option<(Vec<u8>, ExifTag)> {
+    let start = jpeg::EXIF_SIGNATURE.len();
+    if !exif.starts_with(jpeg::EXIF_SIGNATURE) {
+        return None;
+    }
+
+    let tiff = Tiff::new(&exif[start..])?;
+    let ifd = tiff.sub_ifd(
+        tiff.first_ifd()?,
+        ExifTag::EXIF_TAG_EXIF_IFD_POINTER.0 as u16,
+    )?;
+    let entry = start + tiff.find_entry(ifd, ExifTag::EXIF_TAG_MAKER_NOTE.0 as u16)?;
+    let placeholder = MAKER_NOTE_PLACEHOLDERS
+        .into_iter()
+        .find(|tag| tiff.find_entry(ifd, tag.0 as u16).is_none())?;
+
+    let mut patched = exif.to_vec();
+    let tag = tiff.encode_u16(placeholder.0 as u16);
+    patched[entry..entry + 2].copy_from_slice(&tag);
+
+    Some((patched, placeholder))
+}
+
+/// Warn when the EXIF data reaches past the end of its APP1 segment, which happens when the
+/// length of the segment is wrong. libexif only parses the declared length so the entries
+/// beyond it go missing.
+fn check_segment_length(jpeg: &[u8], segment: &ExifSegment, log: &WarningLog) {
+    let tiff = match Tiff::new(&jpeg[segment.tiff_header..]) {
+        Some(tiff) => tiff,
+        None => return,
+    };
+
+    let declared = segment.payload.end - segment.tiff_header;
+    let extent = tiff.extent();
+    if extent > declared {
+        log.push(format!(
+            "APP1 segment has room for {} bytes of EXIF data but its IFDs reach {} bytes, \
+             its length is probably wrong",
+            declared, extent
+        ));
+    }
+}
+
+pub(crate) fn invalid_data() -> ExifError {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid EXIF data").into()
+}
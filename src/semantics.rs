@@ -0,0 +1,418 @@
+use crate::bindings::ExifTag;
+use crate::bits::*;
+use crate::data::Data;
+use crate::value::{Rational, Value};
+
+/// Channel stored in one of the four components described by `ComponentsConfiguration`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Component {
+    /// The component doesn't exist.
+    None,
+
+    /// Luminance.
+    Y,
+
+    /// Blue chrominance.
+    Cb,
+
+    /// Red chrominance.
+    Cr,
+
+    /// Red.
+    R,
+
+    /// Green.
+    G,
+
+    /// Blue.
+    B,
+
+    /// Value reserved by the EXIF specification.
+    Reserved(u8),
+}
+
+impl From<u8> for Component {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Component::None,
+            1 => Component::Y,
+            2 => Component::Cb,
+            3 => Component::Cr,
+            4 => Component::R,
+            5 => Component::G,
+            6 => Component::B,
+            value => Component::Reserved(value),
+        }
+    }
+}
+
+/// Type of scene described by `SceneType`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SceneType {
+    /// The image was directly photographed.
+    DirectlyPhotographed,
+
+    /// Value reserved by the EXIF specification.
+    Reserved(u8),
+}
+
+impl From<u8> for SceneType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => SceneType::DirectlyPhotographed,
+            value => SceneType::Reserved(value),
+        }
+    }
+}
+
+/// Source of the image described by `FileSource`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FileSource {
+    /// Some other source.
+    Other,
+
+    /// Scanner of transparent type, e.g., a film scanner.
+    TransparentScanner,
+
+    /// Scanner of reflex type, e.g., a flatbed scanner.
+    ReflexScanner,
+
+    /// Digital still camera.
+    DigitalCamera,
+
+    /// Value reserved by the EXIF specification.
+    Reserved(u8),
+}
+
+impl From<u8> for FileSource {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => FileSource::Other,
+            1 => FileSource::TransparentScanner,
+            2 => FileSource::ReflexScanner,
+            3 => FileSource::DigitalCamera,
+            value => FileSource::Reserved(value),
+        }
+    }
+}
+
+/// Firing mode of the flash, from bits 3 and 4 of `Flash`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FlashMode {
+    /// The mode is unknown.
+    Unknown,
+
+    /// The flash is forced to fire.
+    On,
+
+    /// The flash is prevented from firing.
+    Off,
+
+    /// The camera decides whether the flash fires.
+    Auto,
+}
+
+/// Detection of the light returned by the flash's strobe, from bits 1 and 2 of `Flash`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FlashReturn {
+    /// The camera can't detect the strobe's return light.
+    NoDetection,
+
+    /// Value reserved by the EXIF specification.
+    Reserved,
+
+    /// The strobe's return light wasn't detected.
+    NotDetected,
+
+    /// The strobe's return light was detected.
+    Detected,
+}
+
+/// Status of the flash when the image was taken, decoded from the bits of `Flash`.
+///
+/// # Example
+/// ```rust
+/// use libexif::{Flash, FlashMode, FlashReturn};
+/// let flash = Flash::from(0x59);
+/// assert!(flash.fired);
+/// assert_eq!(flash.mode, FlashMode::Auto);
+/// assert_eq!(flash.return_light, FlashReturn::NoDetection);
+/// assert!(flash.present);
+/// assert!(flash.red_eye_reduction);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Flash {
+    /// Whether the flash fired.
+    pub fired: bool,
+
+    /// Detection of the strobe's return light.
+    pub return_light: FlashReturn,
+
+    /// Firing mode of the flash.
+    pub mode: FlashMode,
+
+    /// Whether the camera has a flash.
+    pub present: bool,
+
+    /// Whether red-eye reduction was enabled.
+    pub red_eye_reduction: bool,
+}
+
+impl From<u16> for Flash {
+    fn from(value: u16) -> Self {
+        Flash {
+            fired: value & 0x01 != 0,
+            return_light: match (value >> 1) & 0x03 {
+                0 => FlashReturn::NoDetection,
+                1 => FlashReturn::Reserved,
+                2 => FlashReturn::NotDetected,
+                _ => FlashReturn::Detected,
+            },
+            mode: match (value >> 3) & 0x03 {
+                0 => FlashMode::Unknown,
+                1 => FlashMode::On,
+                2 => FlashMode::Off,
+                _ => FlashMode::Auto,
+            },
+            present: value & 0x20 == 0,
+            red_eye_reduction: value & 0x40 != 0,
+        }
+    }
+}
+
+/// Converts an APEX shutter speed, as in `ShutterSpeedValue`, to an exposure time in seconds.
+///
+/// The exposure time is `2^-value`, so `Rational(6, 1)` is 1/64 seconds. A zero denominator
+/// results in an infinite or NaN exposure time.
+///
+/// # Example
+/// ```rust
+/// use libexif::{apex_shutter_speed_to_seconds, Rational};
+/// assert_eq!(apex_shutter_speed_to_seconds(Rational(6, 1)), 1.0 / 64.0);
+/// assert_eq!(apex_shutter_speed_to_seconds(Rational(-1, 1)), 2.0);
+/// ```
+pub fn apex_shutter_speed_to_seconds(value: Rational<i32>) -> f64 {
+    (-(f64::from(value.0) / f64::from(value.1))).exp2()
+}
+
+/// Converts an exposure time in seconds to an APEX shutter speed, the inverse of
+/// [`apex_shutter_speed_to_seconds()`](fn.apex_shutter_speed_to_seconds.html).
+pub fn seconds_to_apex_shutter_speed(seconds: f64) -> f64 {
+    -seconds.log2()
+}
+
+/// Converts an APEX aperture, as in `ApertureValue` or `MaxApertureValue`, to an f-number.
+///
+/// The f-number is `2^(value / 2)`, so `Rational(5, 1)` is f/5.66. A zero denominator results in
+/// an infinite or NaN f-number.
+///
+/// # Example
+/// ```rust
+/// use libexif::{apex_aperture_to_fnumber, Rational};
+/// assert_eq!(apex_aperture_to_fnumber(Rational(4, 1)), 4.0);
+/// assert!((apex_aperture_to_fnumber(Rational(5, 1)) - 5.657).abs() < 0.001);
+/// ```
+pub fn apex_aperture_to_fnumber(value: Rational<u32>) -> f64 {
+    (f64::from(value.0) / f64::from(value.1) / 2.0).exp2()
+}
+
+/// Converts an f-number to an APEX aperture, the inverse of
+/// [`apex_aperture_to_fnumber()`](fn.apex_aperture_to_fnumber.html).
+pub fn fnumber_to_apex_aperture(fnumber: f64) -> f64 {
+    2.0 * fnumber.log2()
+}
+
+/// Converts an APEX brightness, as in `BrightnessValue`, to a luminance in candelas per square
+/// metre.
+///
+/// The luminance is `2^value` foot-lamberts, one foot-lambert being `1 / π` candela per square
+/// foot. A numerator of `0xFFFFFFFF` means the brightness is unknown, which returns `None`.
+///
+/// # Example
+/// ```rust
+/// use libexif::{apex_brightness_to_luminance, Rational};
+/// assert!((apex_brightness_to_luminance(Rational(0, 1)).unwrap() - 3.426).abs() < 0.001);
+/// assert_eq!(apex_brightness_to_luminance(Rational(-1, 1)), None);
+/// ```
+pub fn apex_brightness_to_luminance(value: Rational<i32>) -> Option<f64> {
+    if value.0 as u32 == u32::MAX {
+        return None;
+    }
+
+    const FOOT_LAMBERT: f64 = 3.426_259;
+    Some((f64::from(value.0) / f64::from(value.1)).exp2() * FOOT_LAMBERT)
+}
+
+impl Data {
+    /// Returns the status of the flash when the image was taken, from `Flash`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Data;
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// if let Some(flash) = data.flash() {
+    ///     println!("flash fired: {}", flash.fired);
+    /// }
+    /// ```
+    pub fn flash(&self) -> Option<Flash> {
+        let entry = self.get_entry(IFD::EXIF, ExifTag::EXIF_TAG_FLASH).ok()?;
+        let flags = entry.value_auto().ok()?.bit_flags()?;
+
+        Some(Flash::from(flags as u16))
+    }
+
+    /// Returns the channel of each component of the compressed image, from
+    /// `ComponentsConfiguration`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Component, Data};
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// if let Some(components) = data.components_configuration() {
+    ///     assert_ne!(components[0], Component::Reserved(7));
+    /// }
+    /// ```
+    pub fn components_configuration(&self) -> Option<[Component; 4]> {
+        let bytes = self.get_bytes(IFD::EXIF, ExifTag::EXIF_TAG_COMPONENTS_CONFIGURATION)?;
+        let bytes: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+
+        Some(bytes.map(Component::from))
+    }
+
+    /// Returns the type of scene, from `SceneType`.
+    pub fn scene_type(&self) -> Option<SceneType> {
+        let bytes = self.get_bytes(IFD::EXIF, ExifTag::EXIF_TAG_SCENE_TYPE)?;
+
+        bytes.first().copied().map(SceneType::from)
+    }
+
+    /// Returns the source of the image, from `FileSource`.
+    pub fn file_source(&self) -> Option<FileSource> {
+        let bytes = self.get_bytes(IFD::EXIF, ExifTag::EXIF_TAG_FILE_SOURCE)?;
+
+        bytes.first().copied().map(FileSource::from)
+    }
+
+    /// Returns the raw `Orientation` of the image, from 1 for upright pixels to 8.
+    pub fn orientation(&self) -> Option<u16> {
+        let orientation = self.get_uint(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION)?;
+
+        u16::try_from(orientation).ok()
+    }
+
+    /// Whether the pixels have to be rotated or flipped to display the image upright, i.e.,
+    /// whether `Orientation` is present with another value than 1.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Value};
+    /// let mut data = Data::new();
+    /// assert!(!data.needs_orientation_fix());
+    ///
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ORIENTATION, Value::U16(vec![6]), order)
+    ///     .unwrap();
+    /// assert!(data.needs_orientation_fix());
+    /// assert_eq!(data.orientation(), Some(6));
+    /// ```
+    pub fn needs_orientation_fix(&self) -> bool {
+        matches!(self.orientation(), Some(orientation) if orientation != 1)
+    }
+
+    /// Returns the ISO speed the image was taken with.
+    ///
+    /// EXIF 2.2 stores the ISO in `ISOSpeedRatings`, which EXIF 2.3 renamed to
+    /// `PhotographicSensitivity`. Being a `SHORT` it saturates at 65535, in which case the actual
+    /// value has to be read from `ISOSpeed`, `RecommendedExposureIndex` or
+    /// `StandardOutputSensitivity`. This checks those tags in that order and falls back to the
+    /// saturated value when none of them are present. Zero values are ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Data;
+    /// let data = Data::open("assets/file.jpg").unwrap();
+    /// if let Some(iso) = data.iso_speed() {
+    ///     println!("ISO {}", iso);
+    /// }
+    /// ```
+    pub fn iso_speed(&self) -> Option<u32> {
+        let sensitivity = self.get_uint(IFD::EXIF, ExifTag::EXIF_TAG_ISO_SPEED_RATINGS);
+        if let Some(iso) = sensitivity.filter(|&iso| iso < u16::MAX as u32) {
+            return Some(iso);
+        }
+
+        [
+            ExifTag::EXIF_TAG_ISO_SPEED,
+            ExifTag::EXIF_TAG_RECOMMENDED_EXPOSURE_INDEX,
+            ExifTag::EXIF_TAG_STANDARD_OUTPUT_SENSITIVITY,
+        ]
+        .into_iter()
+        .find_map(|tag| self.get_uint(IFD::EXIF, tag))
+        .or(sensitivity)
+    }
+
+    /// Returns the horizontal and vertical resolution of the image in dots per inch, from
+    /// `XResolution`, `YResolution` and `ResolutionUnit`.
+    ///
+    /// Resolutions in centimeters are converted, a missing `ResolutionUnit` means inches like
+    /// the specification says. `None` is returned when a resolution is missing or invalid, or
+    /// when the unit is 1, which means the resolution has no absolute unit.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Data, ExifTag, IFD, Rational, Value};
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// for tag in [ExifTag::EXIF_TAG_X_RESOLUTION, ExifTag::EXIF_TAG_Y_RESOLUTION] {
+    ///     data.set_entry(IFD::Image, tag, Value::URational(vec![Rational(118, 1)]), order)
+    ///         .unwrap();
+    /// }
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_RESOLUTION_UNIT, Value::U16(vec![3]), order)
+    ///     .unwrap();
+    ///
+    /// let (x, _) = data.resolution().unwrap();
+    /// assert_eq!(x.round(), 300.0);
+    /// ```
+    pub fn resolution(&self) -> Option<(f64, f64)> {
+        let x = self.get_urational(IFD::Image, ExifTag::EXIF_TAG_X_RESOLUTION)?;
+        let y = self.get_urational(IFD::Image, ExifTag::EXIF_TAG_Y_RESOLUTION)?;
+
+        let per_inch = match self.get_uint(IFD::Image, ExifTag::EXIF_TAG_RESOLUTION_UNIT) {
+            None | Some(2) => 1.0,
+            Some(3) => 2.54,
+            Some(_) => return None,
+        };
+
+        Some((x * per_inch, y * per_inch))
+    }
+
+    /// The first component of an unsigned rational entry, `None` for a zero denominator.
+    fn get_urational(&self, ifd: IFD, tag: ExifTag) -> Option<f64> {
+        match self.get_entry(ifd, tag).ok()?.value_auto().ok()? {
+            Value::URational(values) => values
+                .first()
+                .filter(|value| value.1 != 0)
+                .map(|value| f64::from(value.0) / f64::from(value.1)),
+            _ => None,
+        }
+    }
+
+    /// The bytes of an `UNDEFINED` or `BYTE` entry.
+    fn get_bytes(&self, ifd: IFD, tag: ExifTag) -> Option<Vec<u8>> {
+        match self.get_entry(ifd, tag).ok()?.value_auto().ok()? {
+            Value::Undefined(bytes) | Value::U8(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The first component of an unsigned integer entry, ignoring zero values.
+    fn get_uint(&self, ifd: IFD, tag: ExifTag) -> Option<u32> {
+        let value = match self.get_entry(ifd, tag).ok()?.value_auto().ok()? {
+            Value::U16(values) => values.first().copied().map(u32::from),
+            Value::U32(values) => values.first().copied(),
+            _ => None,
+        };
+
+        value.filter(|&value| value != 0)
+    }
+}
@@ -1,12 +1,42 @@
 use std::ffi::CStr;
 
+use libc::c_uint;
+
 use crate::bindings::*;
 use crate::ExifError;
 
 use crate::bits::*;
 use crate::internal::*;
+use crate::mem::Allocator;
+
+/// `ASCII` tags the EXIF specification gives a fixed count, with the IFD they're in and the
+/// count, which includes the NUL terminator. Every other `ASCII` tag has any length.
+const FIXED_TEXT_LENGTHS: [(IFD, u16, usize); 21] = [
+    (IFD::Image, 0x0132, 20),           // DateTime
+    (IFD::EXIF, 0x9003, 20),            // DateTimeOriginal
+    (IFD::EXIF, 0x9004, 20),            // DateTimeDigitized
+    (IFD::EXIF, 0x9010, 7),             // OffsetTime
+    (IFD::EXIF, 0x9011, 7),             // OffsetTimeOriginal
+    (IFD::EXIF, 0x9012, 7),             // OffsetTimeDigitized
+    (IFD::EXIF, 0xa004, 13),            // RelatedSoundFile
+    (IFD::EXIF, 0xa420, 33),            // ImageUniqueID
+    (IFD::GPS, 0x0001, 2),              // GPSLatitudeRef
+    (IFD::GPS, 0x0003, 2),              // GPSLongitudeRef
+    (IFD::GPS, 0x0009, 2),              // GPSStatus
+    (IFD::GPS, 0x000a, 2),              // GPSMeasureMode
+    (IFD::GPS, 0x000c, 2),              // GPSSpeedRef
+    (IFD::GPS, 0x000e, 2),              // GPSTrackRef
+    (IFD::GPS, 0x0010, 2),              // GPSImgDirectionRef
+    (IFD::GPS, 0x0013, 2),              // GPSDestLatitudeRef
+    (IFD::GPS, 0x0015, 2),              // GPSDestLongitudeRef
+    (IFD::GPS, 0x0017, 2),              // GPSDestBearingRef
+    (IFD::GPS, 0x0019, 2),              // GPSDestDistanceRef
+    (IFD::GPS, 0x001d, 11),             // GPSDateStamp
+    (IFD::Interoperability, 0x0001, 4), // InteroperabilityIndex
+];
 
 /// EXIF tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Tag {
     inner: ExifTag,
 }
@@ -24,6 +54,11 @@ impl ToLibExif<ExifTag> for Tag {
 }
 
 impl Tag {
+    /// The numeric ID of the EXIF tag, e.g., `0x0112` for `Orientation`.
+    pub fn id(&self) -> u16 {
+        self.inner.0 as u16
+    }
+
     /// The name of the EXIF tag when found in the given IFD.
     pub fn name(&self, ifd: IFD) -> &'static str {
         let ptr = unsafe { exif_tag_get_name_in_ifd(self.inner, ifd.to_libexif()) };
@@ -65,20 +100,184 @@ impl Tag {
 
         SupportLevel::from_libexif(support_level)
     }
+
+    /// Whether the EXIF specification allows the tag in the given IFD, i.e., whether its
+    /// support level is `Required` or `Optional` for any of the data encodings.
+    ///
+    /// Like [`Data::set_entry()`](struct.Data.html#method.set_entry), the thumbnail IFD accepts
+    /// the tags allowed in the image IFD.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ExifTag, IFD, Tag};
+    /// let tag = Tag::from(ExifTag::EXIF_TAG_ORIENTATION);
+    /// assert!(tag.writable_in(IFD::Image));
+    /// assert!(tag.writable_in(IFD::Thumbnail));
+    /// assert!(!tag.writable_in(IFD::GPS));
+    /// ```
+    pub fn writable_in(&self, ifd: IFD) -> bool {
+        let allowed = |ifd: IFD| {
+            [
+                DataEncoding::Chunky,
+                DataEncoding::Planar,
+                DataEncoding::Ycc,
+                DataEncoding::Compressed,
+            ]
+            .into_iter()
+            .any(|encoding| self.support_level(ifd, encoding).is_allowed())
+        };
+
+        allowed(ifd) || (ifd == IFD::Thumbnail && allowed(IFD::Image))
+    }
+}
+
+impl Tag {
+    /// The data type libexif uses for the EXIF tag in the given IFD.
+    ///
+    /// This is the format of the default entry `exif_entry_initialize` creates, `None` is
+    /// returned when libexif doesn't know the tag in the IFD or has no default for it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataType, ExifTag, IFD, Tag};
+    /// let tag = Tag::from(ExifTag::EXIF_TAG_X_RESOLUTION);
+    /// assert_eq!(tag.expected_format(IFD::Image), Some(DataType::URational));
+    /// assert_eq!(tag.expected_components(IFD::Image), Some(1));
+    /// ```
+    pub fn expected_format(&self, ifd: IFD) -> Option<DataType> {
+        self.expected_layout(ifd).map(|(data_type, _)| data_type)
+    }
+
+    /// The number of components libexif uses for the EXIF tag in the given IFD.
+    ///
+    /// See [`expected_format()`](#method.expected_format). The count of text tags comes from
+    /// the EXIF specification instead, `None` is returned for text of variable length such as
+    /// `Artist`, and when the default entry has no components.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ExifTag, IFD, Tag};
+    /// let tag = Tag::from(ExifTag::EXIF_TAG_DATE_TIME);
+    /// assert_eq!(tag.expected_components(IFD::Image), Some(20));
+    /// assert_eq!(Tag::from(ExifTag::EXIF_TAG_ARTIST).expected_components(IFD::Image), None);
+    /// ```
+    pub fn expected_components(&self, ifd: IFD) -> Option<u64> {
+        match self.expected_layout(ifd)? {
+            (DataType::Text, _) => self.fixed_text_len(ifd).map(|len| len as u64),
+            (_, components) => Some(components).filter(|&components| components != 0),
+        }
+    }
+
+    /// Number of bytes, with the NUL terminator, of a text tag the EXIF specification gives a
+    /// fixed length in the given IFD, e.g., 2 for `GPSLatitudeRef` and 20 for `DateTime`.
+    pub(crate) fn fixed_text_len(&self, ifd: IFD) -> Option<usize> {
+        // The thumbnail IFD holds the same tags as the image IFD
+        let ifd = match ifd {
+            IFD::Thumbnail => IFD::Image,
+            ifd => ifd,
+        };
+
+        FIXED_TEXT_LENGTHS
+            .iter()
+            .find(|&&(table_ifd, id, _)| table_ifd == ifd && id == self.id())
+            .map(|&(_, _, len)| len)
+    }
+
+    /// Format and components of the default entry libexif creates for the tag.
+    fn expected_layout(&self, ifd: IFD) -> Option<(DataType, u64)> {
+        if ifd == IFD::Count
+            || unsafe { exif_tag_get_name_in_ifd(self.inner, ifd.to_libexif()) }.is_null()
+        {
+            return None;
+        }
+
+        // exif_entry_initialize needs the entry to be part of some EXIF data
+        let data = unsafe { exif_data_new() };
+        if data.is_null() {
+            return None;
+        }
+        let entry = unsafe { exif_entry_new() };
+        if entry.is_null() {
+            unsafe { exif_data_unref(data) };
+            return None;
+        }
+
+        let (format, components) = unsafe {
+            exif_content_add_entry((*data).ifd[ifd.to_libexif() as usize], entry);
+            exif_entry_initialize(entry, self.inner);
+
+            // The format stays 0 when libexif has no default for the tag, which isn't a valid
+            // ExifFormat so read it as an integer
+            let format = std::ptr::addr_of!((*entry).format).cast::<c_uint>().read();
+            let components = (*entry).components;
+
+            exif_entry_unref(entry);
+            exif_data_unref(data);
+
+            (format, components)
+        };
+
+        let data_type = [
+            DataType::Text,
+            DataType::U8,
+            DataType::I8,
+            DataType::U16,
+            DataType::I16,
+            DataType::U32,
+            DataType::I32,
+            DataType::URational,
+            DataType::IRational,
+            DataType::Undefined,
+        ]
+        .into_iter()
+        .find(|data_type| data_type.to_libexif() as c_uint == format)?;
+
+        Some((data_type, components))
+    }
+}
+
+impl From<ExifTag> for Tag {
+    fn from(tag: ExifTag) -> Tag {
+        Tag::from_libexif(tag)
+    }
+}
+
+impl From<Tag> for ExifTag {
+    fn from(tag: Tag) -> ExifTag {
+        tag.to_libexif()
+    }
+}
+
+/// All the tags in libexif's tag table, without duplicates.
+pub(crate) fn known_tags() -> Vec<ExifTag> {
+    let count = unsafe { exif_tag_table_count() };
+    let mut tags = Vec::with_capacity(count as usize);
+
+    for index in 0..count {
+        // The table is terminated by an entry without a name
+        if unsafe { exif_tag_table_get_name(index) }.is_null() {
+            continue;
+        }
+
+        let tag = unsafe { exif_tag_table_get_tag(index) };
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    tags
 }
 
 pub(crate) fn create_tag(
     exif: ExifData,
+    mem: &Allocator,
     ifd: impl ToLibExif<ExifIfd>,
     tag: ExifTag,
     components: u64,
     length: u32,
     format: ExifFormat,
 ) -> Result<ExifEntry, ExifError> {
-    let mem = unsafe { exif_mem_new_default() };
-    if mem.is_null() {
-        return Err(ExifError::MemNewFail);
-    }
+    let mem = mem.as_ptr();
     let entry = unsafe { exif_entry_new_mem(mem) };
     if entry.is_null() {
         return Err(ExifError::EntryNewFail);
@@ -102,9 +301,8 @@ pub(crate) fn create_tag(
     unsafe { exif_content_add_entry(exif.ifd[ifd.to_libexif() as usize], entry) };
 
     unsafe { exif_entry_initialize(entry, tag) };
-    // The ExifMem and ExifEntry are now owned by the ExifData so unref them
+    // The ExifEntry is now owned by the ExifData so unref it
     unsafe {
-        exif_mem_unref(mem);
         exif_entry_unref(entry);
     }
 
@@ -0,0 +1,262 @@
+use crate::bits::IFD;
+
+/// Size of an IFD entry: tag, format, components and value or offset.
+const ENTRY_SIZE: usize = 12;
+
+/// Tags of the entries pointing to the EXIF, GPS and interoperability IFDs.
+const POINTER_TAGS: [u16; 3] = [0x8769, 0x8825, 0xa005];
+
+/// Tags of the offset and length of the thumbnail.
+const THUMBNAIL_OFFSET: u16 = 0x0201;
+const THUMBNAIL_LENGTH: u16 = 0x0202;
+
+/// Upper bound on the IFDs visited, in case the offsets form a loop.
+const MAX_IFDS: usize = 16;
+
+/// Location of an IFD of the EXIF data in the file, see
+/// [`Data::dump_offsets()`](struct.Data.html#method.dump_offsets).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfdOffsetInfo {
+    /// The IFD.
+    pub ifd: IFD,
+
+    /// Position of the IFD, i.e., of its entry count.
+    pub offset: usize,
+
+    /// Raw offset of the next IFD, relative to the TIFF header, or `None` if it's past the end
+    /// of the data. Only IFD0 is expected to have a non-zero one, pointing at IFD1.
+    pub next_ifd: Option<u32>,
+
+    /// Entries of the IFD in the order they are stored.
+    pub entries: Vec<EntryOffsetInfo>,
+}
+
+/// Location of an IFD entry and of the value it points at, see
+/// [`IfdOffsetInfo`](struct.IfdOffsetInfo.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryOffsetInfo {
+    /// Numeric id of the tag.
+    pub tag: u16,
+
+    /// Raw number of the TIFF format.
+    pub format: u16,
+
+    /// Number of components.
+    pub components: u32,
+
+    /// Position of the entry.
+    pub offset: usize,
+
+    /// Position of the value, or `None` if the value fits in the entry.
+    pub value_offset: Option<usize>,
+
+    /// Number of bytes of the value, 0 for unknown formats.
+    pub value_length: usize,
+}
+
+/// Minimal reader for the TIFF structure of EXIF data, used where the data has to be looked at
+/// before libexif parses it.
+pub(crate) struct Tiff<'a> {
+    buf: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    /// Read the TIFF header at the start of the buffer.
+    pub(crate) fn new(buf: &'a [u8]) -> Option<Self> {
+        let big_endian = match buf.get(..4)? {
+            b"II*\0" => false,
+            b"MM\0*" => true,
+            _ => return None,
+        };
+
+        Some(Tiff { buf, big_endian })
+    }
+
+    pub(crate) fn u16(&self, pos: usize) -> Option<u16> {
+        let bytes = self.buf.get(pos..pos.checked_add(2)?)?.try_into().ok()?;
+
+        Some(match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    }
+
+    pub(crate) fn u32(&self, pos: usize) -> Option<u32> {
+        let bytes = self.buf.get(pos..pos.checked_add(4)?)?.try_into().ok()?;
+
+        Some(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Encode a 16-bit integer in the byte order of the data.
+    pub(crate) fn encode_u16(&self, value: u16) -> [u8; 2] {
+        match self.big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        }
+    }
+
+    /// Offset of IFD0.
+    pub(crate) fn first_ifd(&self) -> Option<usize> {
+        self.u32(4).map(|offset| offset as usize)
+    }
+
+    /// Offset of the IFD a pointer tag such as `ExifIfdPointer` refers to.
+    pub(crate) fn sub_ifd(&self, ifd: usize, pointer: u16) -> Option<usize> {
+        let entry = self.find_entry(ifd, pointer)?;
+
+        self.u32(entry + 8).map(|offset| offset as usize)
+    }
+
+    /// Offsets of the entries of the IFD at the given offset.
+    pub(crate) fn entries(&self, ifd: usize) -> Option<impl Iterator<Item = usize>> {
+        let count = self.u16(ifd)? as usize;
+        let start = ifd + 2;
+        let end = start + count * ENTRY_SIZE;
+        if end > self.buf.len() {
+            return None;
+        }
+
+        Some((start..end).step_by(ENTRY_SIZE))
+    }
+
+    /// Number of bytes the TIFF structure spans from the header, i.e., the end of the IFD, value
+    /// or thumbnail that ends last.
+    ///
+    /// IFDs that aren't in the buffer only count with their entry count, so the extent is a
+    /// lower bound when the buffer is cut short.
+    pub(crate) fn extent(&self) -> usize {
+        let mut extent = 8;
+        let mut pending = Vec::from_iter(self.first_ifd());
+        let mut visited = Vec::new();
+
+        while let Some(ifd) = pending.pop() {
+            if ifd == 0 || visited.contains(&ifd) || visited.len() == MAX_IFDS {
+                continue;
+            }
+            visited.push(ifd);
+
+            extent = extent.max(ifd.saturating_add(2));
+            let entries = match self.entries(ifd) {
+                Some(entries) => entries,
+                None => continue,
+            };
+
+            let mut thumbnail = (None, None);
+            for entry in entries {
+                extent = extent.max(entry + ENTRY_SIZE);
+
+                let tag = self.u16(entry).unwrap_or(0);
+                let value = self.u32(entry + 8).map(|value| value as usize);
+                match tag {
+                    _ if POINTER_TAGS.contains(&tag) => pending.extend(value),
+                    THUMBNAIL_OFFSET => thumbnail.0 = value,
+                    THUMBNAIL_LENGTH => thumbnail.1 = value,
+                    _ => {}
+                }
+
+                let size = self
+                    .u16(entry + 2)
+                    .map(format_size)
+                    .zip(self.u32(entry + 4))
+                    .map(|(size, components)| size.saturating_mul(components as usize));
+                if let (Some(size), Some(offset)) = (size.filter(|&size| size > 4), value) {
+                    extent = extent.max(offset.saturating_add(size));
+                }
+            }
+            if let (Some(offset), Some(length)) = thumbnail {
+                extent = extent.max(offset.saturating_add(length));
+            }
+
+            // The IFD0 chain continues with IFD1
+            let next = ifd + 2 + self.u16(ifd).unwrap_or(0) as usize * ENTRY_SIZE;
+            extent = extent.max(next + 4);
+            pending.extend(self.u32(next).map(|offset| offset as usize));
+        }
+
+        extent
+    }
+
+    /// Describe where the IFDs, their entries and values are, with positions relative to the
+    /// TIFF header shifted by `base`.
+    ///
+    /// IFDs are followed from IFD0 the same way libexif does: IFD1 through the next IFD offset of
+    /// IFD0 and the others through their pointer tags. IFDs outside the buffer are left out.
+    pub(crate) fn ifd_offsets(&self, base: usize) -> Vec<IfdOffsetInfo> {
+        let mut infos: Vec<IfdOffsetInfo> = Vec::new();
+        let mut pending = Vec::from_iter(self.first_ifd().map(|offset| (IFD::Image, offset)));
+
+        while let Some((ifd, offset)) = pending.pop() {
+            if offset == 0
+                || infos.len() == MAX_IFDS
+                || infos.iter().any(|info| info.offset == base + offset)
+            {
+                continue;
+            }
+            let entries = match self.entries(offset) {
+                Some(entries) => entries,
+                None => continue,
+            };
+
+            let mut info = IfdOffsetInfo {
+                ifd,
+                offset: base + offset,
+                next_ifd: None,
+                entries: Vec::new(),
+            };
+            for entry in entries {
+                let tag = self.u16(entry).unwrap_or(0);
+                let format = self.u16(entry + 2).unwrap_or(0);
+                let components = self.u32(entry + 4).unwrap_or(0);
+                let value = self.u32(entry + 8).unwrap_or(0) as usize;
+
+                let sub_ifd = match tag {
+                    0x8769 if ifd == IFD::Image => Some(IFD::EXIF),
+                    0x8825 if ifd == IFD::Image => Some(IFD::GPS),
+                    0xa005 if ifd == IFD::EXIF => Some(IFD::Interoperability),
+                    _ => None,
+                };
+                pending.extend(sub_ifd.map(|sub_ifd| (sub_ifd, value)));
+
+                let value_length = format_size(format).saturating_mul(components as usize);
+                info.entries.push(EntryOffsetInfo {
+                    tag,
+                    format,
+                    components,
+                    offset: base + entry,
+                    value_offset: (value_length > 4).then(|| base.saturating_add(value)),
+                    value_length,
+                });
+            }
+
+            let next = offset + 2 + info.entries.len() * ENTRY_SIZE;
+            info.next_ifd = self.u32(next);
+            if ifd == IFD::Image {
+                pending.extend(info.next_ifd.map(|next| (IFD::Thumbnail, next as usize)));
+            }
+            infos.push(info);
+        }
+
+        infos
+    }
+
+    /// Offset of the entry with the given tag in the IFD at the given offset.
+    pub(crate) fn find_entry(&self, ifd: usize, tag: u16) -> Option<usize> {
+        self.entries(ifd)?
+            .find(|&entry| self.u16(entry) == Some(tag))
+    }
+}
+
+/// Size of a single component of the TIFF format with the given number.
+fn format_size(format: u16) -> usize {
+    match format {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 0,
+    }
+}
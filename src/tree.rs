@@ -0,0 +1,73 @@
+use crate::bits::*;
+use crate::data::Data;
+use crate::tag::Tag;
+use crate::value::Value;
+
+/// An IFD and the entries it contains, as returned by
+/// [`Data::to_tree()`](struct.Data.html#method.to_tree).
+#[derive(Debug, Clone)]
+pub struct IfdNode {
+    /// The IFD.
+    pub ifd: IFD,
+
+    /// The entries found in the IFD.
+    pub entries: Vec<EntryNode>,
+}
+
+/// A decoded EXIF entry, as returned by [`Data::to_tree()`](struct.Data.html#method.to_tree).
+#[derive(Debug, Clone)]
+pub struct EntryNode {
+    /// The entry's tag.
+    pub tag: Tag,
+
+    /// The data type of the entry's value.
+    pub type_: DataType,
+
+    /// The number of components in the entry's value.
+    pub components: usize,
+
+    /// Textual representation of the entry's value, as formatted by libexif.
+    pub text: String,
+
+    /// The entry's value, decoded with the data's byte order, or its raw bytes as `Undefined`
+    /// when it can't be decoded.
+    pub value: Value,
+}
+
+impl Data {
+    /// Decode all the entries into a tree of IFDs and their entries.
+    ///
+    /// This is the structured counterpart to [`dump()`](#method.dump).
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    ///
+    /// for node in data.to_tree() {
+    ///     println!("{:?}", node.ifd);
+    ///
+    ///     for entry in node.entries {
+    ///         println!("  {:<30} = {}", entry.tag.title(node.ifd), entry.text);
+    ///     }
+    /// }
+    /// ```
+    pub fn to_tree(&self) -> Vec<IfdNode> {
+        self.contents()
+            .map(|content| IfdNode {
+                ifd: content.ifd(),
+                entries: content
+                    .entries()
+                    .map(|entry| EntryNode {
+                        tag: entry.tag(),
+                        type_: entry.data_type(),
+                        components: entry.components(),
+                        text: entry.text_value().unwrap_or_default(),
+                        value: entry
+                            .value_auto()
+                            .unwrap_or_else(|_| Value::Undefined(entry.raw_data().to_vec())),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
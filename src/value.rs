@@ -1,13 +1,20 @@
-use libc::{self, c_char};
 use std::ffi::CString;
-use std::fmt::{self, Display, Formatter};
+use std::fmt::{self, Display, Formatter, UpperHex};
 use std::mem;
+use std::num::TryFromIntError;
+use std::ops::Neg;
 
 use crate::bindings::*;
 use crate::ExifError;
 
 use crate::bits::*;
+use crate::entry::Entry;
 use crate::internal::*;
+use crate::reader::Charset;
+
+/// Number of components [`Value::to_hex_string()`](enum.Value.html#method.to_hex_string) shows
+/// before cutting the value short.
+const HEX_STRING_LIMIT: usize = 32;
 
 /// A rational number consisting of a numerator and denominator.
 ///
@@ -24,6 +31,33 @@ use crate::internal::*;
 /// assert_eq!(42, ratio.numerator());
 /// assert_eq!(100, ratio.denominator());
 /// ```
+///
+/// Computed values can be checked before they end up as a wrong fraction:
+///
+/// ```
+/// use libexif::Rational;
+/// assert_eq!(None, Rational::<u32>::checked_new(1, 0));
+/// assert!(Rational::<i32>::try_from(Rational(1i64 << 40, 1)).is_err());
+/// assert_eq!(Ok(Rational(-3, 4)), Rational::<i32>::try_from(Rational(-3i64, 4)));
+/// ```
+///
+/// For user-facing output the sign can be moved to the numerator:
+///
+/// ```
+/// use libexif::Rational;
+/// assert_eq!("-1/-2", Rational(-1, -2).to_string());
+/// assert_eq!("1/2", Rational::<i32>(-1, -2).display_normalized());
+/// assert_eq!("NaN", Rational::<u32>(0, 0).display_normalized());
+/// ```
+///
+/// Signed rationals can be negated:
+///
+/// ```
+/// use libexif::Rational;
+/// assert_eq!(Rational(-1, 3), -Rational(1, 3));
+/// assert_eq!(Rational(1, 3), Rational(-1, -3).abs());
+/// assert_eq!(Rational(i32::MAX, 1), -Rational(i32::MIN, 1));
+/// ```
 #[repr(C)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Rational<T>(pub T, pub T);
@@ -40,26 +74,238 @@ impl<T: Copy> Rational<T> {
     }
 }
 
+macro_rules! rational_checked {
+    ($($type: ty, $wide: ty;)*) => {
+        $(
+            impl Rational<$type> {
+                /// Returns a rational number, or `None` when the denominator is zero.
+                pub fn checked_new(numerator: $type, denominator: $type) -> Option<Self> {
+                    if denominator == 0 {
+                        None
+                    } else {
+                        Some(Rational(numerator, denominator))
+                    }
+                }
+            }
+
+            impl Rational<$type> {
+                /// Formats the fraction for display with the sign on the numerator, e.g., `-1/-2`
+                /// as `1/2` and `0/-5` as `0/5`.
+                ///
+                /// A zero denominator is shown as `NaN` for `0/0` and as `inf` or `-inf`
+                /// otherwise. Use `Display` to show the fraction as it is stored.
+                pub fn display_normalized(&self) -> String {
+                    display_normalized(i64::from(self.0), i64::from(self.1))
+                }
+
+                /// Formats the number the way a photo app shows it: reduced, whole numbers
+                /// without a denominator, fractions of one like exposure times as `1/x` and
+                /// everything else as a decimal with up to 3 digits.
+                ///
+                /// A zero denominator is shown like
+                /// [`display_normalized()`](#method.display_normalized) does.
+                ///
+                /// # Example
+                /// ```rust
+                /// use libexif::Rational;
+                /// assert_eq!(Rational(1u32, 250).to_display_string(), "1/250");
+                /// assert_eq!(Rational(10u32, 5).to_display_string(), "2");
+                /// assert_eq!(Rational(2u32, 3).to_display_string(), "0.667");
+                /// assert_eq!(Rational(-3i32, -2).to_display_string(), "1.5");
+                /// ```
+                pub fn to_display_string(&self) -> String {
+                    display_friendly(i64::from(self.0), i64::from(self.1))
+                }
+            }
+
+            /// Fails when the numerator or the denominator doesn't fit in the narrower type.
+            impl TryFrom<Rational<$wide>> for Rational<$type> {
+                type Error = TryFromIntError;
+
+                fn try_from(rational: Rational<$wide>) -> Result<Self, Self::Error> {
+                    Ok(Rational(rational.0.try_into()?, rational.1.try_into()?))
+                }
+            }
+        )*
+    };
+}
+
+rational_checked! {
+    u32, u64;
+    i32, i64;
+}
+
+impl Rational<u32> {
+    /// Returns the fraction closest to `value` with a denominator of at most `max_denominator`,
+    /// e.g., to store a decimal number a user typed in as a rational.
+    ///
+    /// Fails with `UnrepresentableRational` when the value is negative, NaN, infinite or too
+    /// large for the numerator, instead of returning a fraction that means something else. A
+    /// `max_denominator` of 0 is treated as 1.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Rational;
+    /// assert_eq!(Rational::try_from_f64(0.5, 100).unwrap(), Rational(1, 2));
+    /// assert_eq!(Rational::try_from_f64(1.0 / 3.0, 100).unwrap(), Rational(1, 3));
+    /// assert_eq!(Rational::try_from_f64(37.7749, 10000).unwrap(), Rational(377749, 10000));
+    /// assert_eq!(Rational::try_from_f64(3.14159265, 10).unwrap(), Rational(22, 7));
+    ///
+    /// assert!(Rational::try_from_f64(-122.4194, 10000).is_err());
+    /// assert!(Rational::try_from_f64(f64::NAN, 10000).is_err());
+    /// assert!(Rational::try_from_f64(f64::INFINITY, 10000).is_err());
+    /// ```
+    pub fn try_from_f64(value: f64, max_denominator: u32) -> Result<Self, ExifError> {
+        if !value.is_finite() || value < 0.0 || value > u32::MAX as f64 {
+            return Err(ExifError::UnrepresentableRational(value));
+        }
+        let max_numerator = u32::MAX as u64;
+        let max_denominator = max_denominator.max(1) as u64;
+
+        // Continued fraction expansion, (p1, q1) being the last convergent
+        let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+        let mut x = value;
+        loop {
+            let a = x.floor() as u64;
+            let p2 = a.saturating_mul(p1).saturating_add(p0);
+            let q2 = a.saturating_mul(q1).saturating_add(q0);
+            if p2 > max_numerator || q2 > max_denominator {
+                // The best fraction is either the last convergent or the largest semiconvergent
+                let k = match p1 {
+                    0 => (max_denominator - q0) / q1,
+                    _ => ((max_denominator - q0) / q1).min((max_numerator - p0) / p1),
+                };
+                let (p, q) = (p0 + k * p1, q0 + k * q1);
+                let error = |p: u64, q: u64| (p as f64 / q as f64 - value).abs();
+                if error(p, q) < error(p1, q1) {
+                    (p1, q1) = (p, q);
+                }
+                break;
+            }
+
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let fraction = x - x.floor();
+            if fraction < f64::EPSILON {
+                break;
+            }
+            x = 1.0 / fraction;
+        }
+
+        Ok(Rational(p1 as u32, q1 as u32))
+    }
+}
+
+impl Rational<i32> {
+    /// Returns the absolute value, with the sign of both the numerator and the denominator
+    /// removed.
+    ///
+    /// `i32::MIN` saturates to `i32::MAX`, which is off by one.
+    pub fn abs(self) -> Self {
+        Rational(self.0.saturating_abs(), self.1.saturating_abs())
+    }
+}
+
+/// Negates the numerator, `i32::MIN` saturates to `i32::MAX`.
+impl Neg for Rational<i32> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Rational(self.0.saturating_neg(), self.1)
+    }
+}
+
+fn display_friendly(numerator: i64, denominator: i64) -> String {
+    if denominator == 0 {
+        return display_normalized(numerator, denominator);
+    }
+
+    let gcd = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i64;
+    let (numerator, denominator) = (numerator / gcd, denominator / gcd);
+    let (numerator, denominator) = match denominator < 0 {
+        true => (-numerator, -denominator),
+        false => (numerator, denominator),
+    };
+
+    match (numerator, denominator) {
+        (n, 1) => n.to_string(),
+        (n @ (1 | -1), d) => format!("{}/{}", n, d),
+        (n, d) => {
+            let decimal = format!("{:.3}", n as f64 / d as f64);
+            match decimal.trim_end_matches('0').trim_end_matches('.') {
+                // Negative numbers too small to show
+                "-0" => "0".to_string(),
+                decimal => decimal.to_string(),
+            }
+        }
+    }
+}
+
+/// Decode NUL-terminated UTF-16 text, replacing unpaired surrogates.
+pub(crate) fn decode_utf16(bytes: &[u8], order: ByteOrder) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| match order {
+            ByteOrder::BigEndian => u16::from_be_bytes([c[0], c[1]]),
+            ByteOrder::LittleEndian => u16::from_le_bytes([c[0], c[1]]),
+        })
+        .take_while(|&u| u != 0)
+        .collect();
+
+    String::from_utf16_lossy(&units)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn display_normalized(numerator: i64, denominator: i64) -> String {
+    match (numerator, denominator) {
+        (0, 0) => "NaN".to_string(),
+        (n, 0) if n < 0 => "-inf".to_string(),
+        (_, 0) => "inf".to_string(),
+        (n, d) if d < 0 => format!("{}/{}", -n, -d),
+        (n, d) => format!("{}/{}", n, d),
+    }
+}
+
 impl<T: Display + Copy> Display for Rational<T> {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
         fmt.write_fmt(format_args!("{}/{}", self.numerator(), self.denominator()))
     }
 }
 
+/// Formats the numerator and the denominator in hex, each with the given width, e.g.,
+/// `format!("{:08X}", Rational(42u32, 100))` gives `"0000002A/00000064"`.
+impl<T: UpperHex + Copy> UpperHex for Rational<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        UpperHex::fmt(&self.numerator(), fmt)?;
+        fmt.write_str("/")?;
+        UpperHex::fmt(&self.denominator(), fmt)
+    }
+}
+
 /// Dynamic value for an EXIF tag.
 ///
 /// Each variant of `Value` corresponds to a variant of [`DataType`](enum.DataType.html). Each
 /// variant (except for `Text`) contains a `Vec` with a length equal to the number of components in
 /// the corresponding [`Entry`](struct.Entry.html).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     /// Value interpreted as a string.
     Text(String),
 
     /// Value interpreted as unsigned bytes.
+    ///
+    /// Single bytes have no byte order, so the same value is read and written regardless of
+    /// the `ByteOrder` that is passed.
     U8(Vec<u8>),
 
     /// Value interpreted as signed bytes.
+    ///
+    /// Like `U8`, the byte order has no effect on this value.
     I8(Vec<i8>),
 
     /// Value interpreted as unsigned 16-bit integers.
@@ -125,6 +371,14 @@ impl_vec! {
     IRational => Rational<i32>,
 }
 
+/// A flag is stored as a single `SHORT`, 1 for `true` and 0 for `false`, see
+/// [`Value::flag()`](enum.Value.html#method.flag).
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::U16(vec![value as u16])
+    }
+}
+
 impl From<&str> for Value {
     fn from(value: &str) -> Self {
         Self::Text(value.to_string())
@@ -136,6 +390,16 @@ impl From<String> for Value {
     }
 }
 
+/// Decodes the entry using the byte order of the EXIF data it belongs to, see
+/// [`Entry::value_auto()`](struct.Entry.html#method.value_auto).
+impl<'a, 'b> TryFrom<&'b Entry<'a>> for Value {
+    type Error = ExifError;
+
+    fn try_from(entry: &'b Entry<'a>) -> Result<Self, Self::Error> {
+        entry.value_auto()
+    }
+}
+
 macro_rules! unwrap_value {
     (
         $(
@@ -156,7 +420,56 @@ macro_rules! unwrap_value {
 
 }
 
+macro_rules! array_value {
+    (
+        $(
+            $(#[$meta: meta])*
+            $type_name: ident, $data_type: ident => $interal_type: ty,
+        )*
+    ) => {
+        $(
+            paste! {
+                #[doc = "Returns the components of a `" $data_type "` value as an array, or "]
+                #[doc = "`None` when the value is of another type or doesn't have exactly `N` "]
+                #[doc = "components."]
+                $(#[$meta])*
+                pub fn [<as_array_$type_name>]<const N: usize>(
+                    &self,
+                ) -> Option<[$interal_type; N]> {
+                    match self {
+                        Self::$data_type(val) => val.as_slice().try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    }
+}
+
 impl Value {
+    /// A single `SHORT` that is 1 for `true` and 0 for `false`, for tags that are flags.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// assert_eq!(Value::flag(true), Value::U16(vec![1]));
+    /// assert_eq!(Value::from(false), Value::flag(false));
+    /// ```
+    pub fn flag(value: bool) -> Value {
+        value.into()
+    }
+
+    /// A single `BYTE`, e.g., for `GPSAltitudeRef`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// assert_eq!(Value::byte(1), Value::U8(vec![1]));
+    /// ```
+    pub fn byte(value: u8) -> Value {
+        value.into()
+    }
+
     unwrap_value! {
         u8, U8 => Vec<u8>,
         i8, I8 => Vec<i8>,
@@ -167,16 +480,400 @@ impl Value {
         undefined, Undefined => Vec<u8>,
         text, Text => String,
     }
+
+    array_value! {
+        u8, U8 => u8,
+        i8, I8 => i8,
+        u16, U16 => u16,
+        i16, I16 => i16,
+        u32, U32 => u32,
+        i32, I32 => i32,
+        ///
+        /// # Example
+        /// ```rust
+        /// use libexif::{Rational, Value};
+        /// let dms = vec![Rational(48, 1), Rational(51, 1), Rational(24, 100)];
+        /// let latitude = Value::URational(dms);
+        /// let [degrees, minutes, seconds] = latitude.as_array_urational::<3>().unwrap();
+        /// assert_eq!(degrees, Rational(48, 1));
+        /// assert_eq!(seconds, Rational(24, 100));
+        /// assert_eq!(latitude.as_array_urational::<2>(), None);
+        /// ```
+        urational, URational => Rational<u32>,
+        irational, IRational => Rational<i32>,
+        ///
+        /// # Example
+        /// ```rust
+        /// use libexif::Value;
+        /// // ComponentsConfiguration, Y Cb Cr
+        /// let components = Value::Undefined(vec![1, 2, 3, 0]);
+        /// assert_eq!(components.as_array_undefined::<4>(), Some([1, 2, 3, 0]));
+        /// ```
+        undefined, Undefined => u8,
+    }
+
+    /// Returns the text of a `Text`, `U8` or `Undefined` value, cut at the first NUL byte and
+    /// without trailing whitespace.
+    ///
+    /// Fixed-length fields are often padded with NULs or spaces, so this is the most reliable
+    /// way to get clean text out of them. Bytes that aren't valid UTF-8 are replaced with
+    /// `U+FFFD`. Other values return `None`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// let model = Value::U8(b"Camera 1  \0\0\0garbage".to_vec());
+    /// assert_eq!(model.as_ascii_string().as_deref(), Some("Camera 1"));
+    ///
+    /// let make = Value::Text("Maker\0\0\0".to_string());
+    /// assert_eq!(make.as_ascii_string().as_deref(), Some("Maker"));
+    /// ```
+    pub fn as_ascii_string(&self) -> Option<String> {
+        let bytes = match self {
+            Value::Text(text) => text.as_bytes(),
+            Value::U8(bytes) | Value::Undefined(bytes) => bytes,
+            _ => return None,
+        };
+
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let text = String::from_utf8_lossy(&bytes[..len]);
+
+        Some(text.trim_end().to_string())
+    }
+
+    /// Returns the text of a `Text`, `U8` or `Undefined` value, cut at the first NUL byte, or
+    /// its bytes when they aren't valid UTF-8.
+    ///
+    /// Unlike [`as_ascii_string()`](#method.as_ascii_string), invalid bytes aren't replaced
+    /// with `U+FFFD`, so mis-encoded text can be told apart. A `Text` value is always valid but
+    /// may hold replacement characters from decoding, see
+    /// [`Entry::text_checked()`](struct.Entry.html#method.text_checked) to check the raw data
+    /// of an entry. Other values aren't text and return an empty `Err`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// let artist = Value::U8(b"Jane Doe\0".to_vec());
+    /// assert_eq!(artist.text_checked(), Ok("Jane Doe".to_string()));
+    ///
+    /// // "José" in Latin-1
+    /// let artist = Value::Undefined(b"Jos\xe9\0".to_vec());
+    /// assert_eq!(artist.text_checked(), Err(b"Jos\xe9\0".to_vec()));
+    /// ```
+    pub fn text_checked(&self) -> Result<String, Vec<u8>> {
+        let bytes = match self {
+            Value::Text(text) => text.as_bytes(),
+            Value::U8(bytes) | Value::Undefined(bytes) => bytes,
+            _ => return Err(Vec::new()),
+        };
+
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8(bytes[..len].to_vec()).map_err(|_| bytes.to_vec())
+    }
+
+    /// Whether the value is probably printable text, to show an `Undefined` or `U8` value as
+    /// text instead of hex, e.g., a `UserComment` or an `ImageUniqueID`.
+    ///
+    /// The 8-byte character code a `UserComment` starts with is skipped, as are trailing NULs
+    /// and spaces. The remaining bytes have to be printable ASCII or line breaks and tabs, NULs
+    /// are allowed too after a `UNICODE` code. `Text` values always look like text, other
+    /// values never do.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// let comment = Value::Undefined(b"ASCII\0\0\0Nice weather\0".to_vec());
+    /// assert!(comment.looks_like_text());
+    ///
+    /// let version = Value::Undefined(vec![0, 2, 3, 1]);
+    /// assert!(!version.looks_like_text());
+    /// ```
+    pub fn looks_like_text(&self) -> bool {
+        let bytes = match self {
+            Value::Text(_) => return true,
+            Value::U8(bytes) | Value::Undefined(bytes) => bytes.as_slice(),
+            _ => return false,
+        };
+
+        let (bytes, allow_nul) = match bytes.split_at_checked(8) {
+            Some((b"ASCII\0\0\0", rest)) | Some((b"JIS\0\0\0\0\0", rest)) => (rest, false),
+            Some((b"UNICODE\0", rest)) => (rest, true),
+            _ => (bytes, false),
+        };
+
+        let len = bytes
+            .iter()
+            .rposition(|&b| b != 0 && b != b' ')
+            .map_or(0, |last| last + 1);
+        let bytes = &bytes[..len];
+
+        !bytes.is_empty()
+            && bytes
+                .iter()
+                .all(|&b| matches!(b, 0x20..=0x7e | b'\t' | b'\n' | b'\r') || (allow_nul && b == 0))
+    }
+
+    /// Renders the value as hex, e.g., to dump a `MakerNote` or another opaque value.
+    ///
+    /// Bytes and the bytes of text are shown as `01 02 FF`, integers are padded to their size,
+    /// e.g., `0001 00FF`, and rationals are shown as `0000002A/00000064`. After 32 components
+    /// the rest of the value is left out and an ellipsis with the total count is added.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// assert_eq!(Value::Undefined(vec![0x30, 0x32, 0x32, 0xff]).to_hex_string(), "30 32 32 FF");
+    /// assert_eq!(Value::U16(vec![1, 255]).to_hex_string(), "0001 00FF");
+    ///
+    /// let maker_note = Value::Undefined(vec![0; 1000]);
+    /// assert!(maker_note.to_hex_string().ends_with("00 00 … (1000 components)"));
+    /// ```
+    pub fn to_hex_string(&self) -> String {
+        fn hex<T: UpperHex>(values: &[T], width: usize) -> String {
+            let mut hex = values
+                .iter()
+                .take(HEX_STRING_LIMIT)
+                .map(|value| format!("{:0width$X}", value, width = width))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if values.len() > HEX_STRING_LIMIT {
+                hex.push_str(&format!(" … ({} components)", values.len()));
+            }
+
+            hex
+        }
+
+        match self {
+            Value::Text(text) => hex(text.as_bytes(), 2),
+            Value::U8(bytes) | Value::Undefined(bytes) => hex(bytes, 2),
+            Value::I8(values) => hex(values, 2),
+            Value::U16(values) => hex(values, 4),
+            Value::I16(values) => hex(values, 4),
+            Value::U32(values) => hex(values, 8),
+            Value::I32(values) => hex(values, 8),
+            Value::URational(values) => hex(values, 8),
+            Value::IRational(values) => hex(values, 8),
+        }
+    }
+
+    /// Decodes a `U8` or `Undefined` value holding UTF-16 text in the given byte order, e.g., a
+    /// vendor tag written by Windows software. Other values return `None`.
+    ///
+    /// The text ends at the first NUL, a trailing odd byte is ignored and unpaired surrogates
+    /// are replaced with `U+FFFD`. There's no byte order mark detection, the byte order
+    /// usually is the one of the EXIF data.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Value};
+    /// let value = Value::U8(vec![0x48, 0x00, 0x69, 0x00, 0x00, 0x00]);
+    /// assert_eq!(value.decode_utf16(ByteOrder::LittleEndian).as_deref(), Some("Hi"));
+    ///
+    /// let value = Value::Undefined(vec![0x00, 0xfc, 0x00, 0x62]);
+    /// assert_eq!(value.decode_utf16(ByteOrder::BigEndian).as_deref(), Some("üb"));
+    /// ```
+    pub fn decode_utf16(&self, order: ByteOrder) -> Option<String> {
+        match self {
+            Value::U8(bytes) | Value::Undefined(bytes) => Some(decode_utf16(bytes, order)),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a `Text` value without the trailing whitespace and NULs cameras pad
+    /// fixed-length fields with. Other values return `None`.
+    ///
+    /// Unlike [`as_ascii_string()`](#method.as_ascii_string), the text isn't cut at the first
+    /// NUL. Use `unwrap_text()` for the text exactly as it is stored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// let make = Value::Text("Canon      \0".to_string());
+    /// assert_eq!(make.text_trimmed().as_deref(), Some("Canon"));
+    /// assert_eq!(Value::U16(vec![1]).text_trimmed(), None);
+    /// ```
+    pub fn text_trimmed(&self) -> Option<String> {
+        match self {
+            Value::Text(text) => {
+                let trimmed = text.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+                Some(trimmed.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of bytes the value occupies once stored in an entry, the number of components
+    /// times the size of the data type, with the NUL terminator for `Text`.
+    ///
+    /// Entries of up to 4 bytes are stored in the IFD itself, larger ones take the 12 bytes of
+    /// the IFD entry on top of this.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{Rational, Value};
+    /// assert_eq!(Value::U16(vec![1, 2, 3]).encoded_len(), 6);
+    /// assert_eq!(Value::URational(vec![Rational(72, 1)]).encoded_len(), 8);
+    /// assert_eq!(Value::Text("Canon".to_string()).encoded_len(), 6);
+    /// ```
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Value::Text(text) => text.len() + 1,
+            Value::U8(values) | Value::Undefined(values) => values.len(),
+            Value::I8(values) => values.len(),
+            Value::U16(values) => values.len() * 2,
+            Value::I16(values) => values.len() * 2,
+            Value::U32(values) => values.len() * 4,
+            Value::I32(values) => values.len() * 4,
+            Value::URational(values) => values.len() * mem::size_of::<Rational<u32>>(),
+            Value::IRational(values) => values.len() * mem::size_of::<Rational<i32>>(),
+        }
+    }
+
+    /// Lazily decode the raw data of an unsigned rational entry, one rational at a time.
+    ///
+    /// Unlike [`Entry::value()`](struct.Entry.html#method.value) nothing is allocated, and
+    /// `nth()` jumps straight to a rational without decoding the ones before it. Trailing bytes
+    /// that don't make up a whole rational are ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Rational, Value};
+    /// let raw = [0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4];
+    /// let second = Value::urationals(&raw, ByteOrder::BigEndian).nth(1);
+    /// assert_eq!(second, Some(Rational(3, 4)));
+    /// ```
+    pub fn urationals(
+        raw_data: &[u8],
+        byte_order: ByteOrder,
+    ) -> impl ExactSizeIterator<Item = Rational<u32>> + DoubleEndedIterator + '_ {
+        raw_data
+            .chunks_exact(mem::size_of::<Rational<u32>>())
+            .map(move |bytes| unsafe { get_urational(bytes.as_ptr(), byte_order.to_libexif()) })
+    }
+
+    /// Same as [`urationals()`](#method.urationals) for the raw data of a signed rational
+    /// entry.
+    pub fn irationals(
+        raw_data: &[u8],
+        byte_order: ByteOrder,
+    ) -> impl ExactSizeIterator<Item = Rational<i32>> + DoubleEndedIterator + '_ {
+        raw_data
+            .chunks_exact(mem::size_of::<Rational<i32>>())
+            .map(move |bytes| unsafe { get_irational(bytes.as_ptr(), byte_order.to_libexif()) })
+    }
+
+    /// The data type of the value.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Value::Text(_) => DataType::Text,
+            Value::U8(_) => DataType::U8,
+            Value::I8(_) => DataType::I8,
+            Value::U16(_) => DataType::U16,
+            Value::I16(_) => DataType::I16,
+            Value::U32(_) => DataType::U32,
+            Value::I32(_) => DataType::I32,
+            Value::URational(_) => DataType::URational,
+            Value::IRational(_) => DataType::IRational,
+            Value::Undefined(_) => DataType::Undefined,
+        }
+    }
+
+    /// Convert an integer value to a larger integer type, when the type can hold every value of
+    /// the current one, see [`DataType::fits_in()`](enum.DataType.html#method.fits_in).
+    ///
+    /// Fails with `FormatMismatch` when the value doesn't fit in the type.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{DataType, Value};
+    /// let value = Value::U8(vec![1, 255]);
+    /// assert_eq!(value.widen_to(DataType::U32).unwrap(), Value::U32(vec![1, 255]));
+    /// assert!(value.widen_to(DataType::I8).is_err());
+    /// ```
+    pub fn widen_to(&self, data_type: DataType) -> Result<Value, ExifError> {
+        fn widen<T: Copy, U: From<T>>(values: &[T]) -> Vec<U> {
+            values.iter().map(|&value| U::from(value)).collect()
+        }
+
+        let widened = match (self, data_type) {
+            (value, data_type) if value.data_type() == data_type => Some(value.clone()),
+            (Value::U8(values), DataType::U16) => Some(Value::U16(widen(values))),
+            (Value::U8(values), DataType::I16) => Some(Value::I16(widen(values))),
+            (Value::U8(values), DataType::U32) => Some(Value::U32(widen(values))),
+            (Value::U8(values), DataType::I32) => Some(Value::I32(widen(values))),
+            (Value::I8(values), DataType::I16) => Some(Value::I16(widen(values))),
+            (Value::I8(values), DataType::I32) => Some(Value::I32(widen(values))),
+            (Value::U16(values), DataType::U32) => Some(Value::U32(widen(values))),
+            (Value::U16(values), DataType::I32) => Some(Value::I32(widen(values))),
+            (Value::I16(values), DataType::I32) => Some(Value::I32(widen(values))),
+            _ => None,
+        };
+
+        widened.ok_or_else(|| {
+            ExifError::FormatMismatch(data_type.to_libexif(), self.data_type().to_libexif())
+        })
+    }
+
+    /// Returns the integer of a single component unsigned value, for testing the bits of tags
+    /// that are bitfields such as `Flash`. Other values return `None`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// let flash = Value::U16(vec![0x19]);
+    /// assert_eq!(flash.bit_flags().map(|flags| flags & 1), Some(1));
+    ///
+    /// assert_eq!(Value::U16(vec![1, 2]).bit_flags(), None);
+    /// ```
+    pub fn bit_flags(&self) -> Option<u32> {
+        match self {
+            Value::U8(values) if values.len() == 1 => Some(values[0].into()),
+            Value::U16(values) if values.len() == 1 => Some(values[0].into()),
+            Value::U32(values) if values.len() == 1 => Some(values[0]),
+            _ => None,
+        }
+    }
+
+    /// Returns a single-component integer value as `u64`, e.g., an offset or a length.
+    ///
+    /// Negative values, values with several components and values that aren't integers
+    /// return `None`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::Value;
+    /// assert_eq!(Value::U32(vec![5120]).as_u64(), Some(5120));
+    /// assert_eq!(Value::I16(vec![-1]).as_u64(), None);
+    /// assert_eq!(Value::U16(vec![1, 2]).as_u64(), None);
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::U8(values) if values.len() == 1 => Some(values[0].into()),
+            Value::U16(values) if values.len() == 1 => Some(values[0].into()),
+            Value::U32(values) if values.len() == 1 => Some(values[0].into()),
+            Value::I8(values) if values.len() == 1 => values[0].try_into().ok(),
+            Value::I16(values) if values.len() == 1 => values[0].try_into().ok(),
+            Value::I32(values) if values.len() == 1 => values[0].try_into().ok(),
+            _ => None,
+        }
+    }
+
+    /// Same as [`as_u64()`](#method.as_u64), also returning `None` when the value doesn't fit
+    /// in a `usize`.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_u64()?.try_into().ok()
+    }
     pub(crate) fn extract(
         raw_data: &[u8],
         data_type: DataType,
         components: usize,
         byte_order: ByteOrder,
+        charset: Charset,
     ) -> Self {
         assert_eq!(raw_data.len(), data_type.size() * components);
 
         match data_type {
-            DataType::Text => Value::Text(extract_text(raw_data, components, byte_order)),
+            DataType::Text => Value::Text(extract_text(raw_data, components, byte_order, charset)),
             DataType::U8 => Value::U8(extract_vec::<u8>(raw_data, components, byte_order, get_u8)),
             DataType::I8 => Value::I8(extract_vec::<i8>(raw_data, components, byte_order, get_i8)),
             DataType::U16 => Value::U16(extract_vec::<u16>(
@@ -232,6 +929,46 @@ impl Value {
             }
         }
     }
+    /// Whether both values are stored as the same bytes in the given byte order.
+    ///
+    /// Unlike `==` this compares what would be written to a file, so it also tells apart
+    /// values that differ only in how they are stored, e.g., text with and without a trailing
+    /// NUL. Values that can't be stored, such as text with a NUL in it, are never equal.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libexif::{ByteOrder, Value};
+    /// let a = Value::U16(vec![0x0102]);
+    /// let b = Value::U8(vec![0x01, 0x02]);
+    /// assert!(a.bytes_eq(&b, ByteOrder::BigEndian));
+    /// assert!(!a.bytes_eq(&b, ByteOrder::LittleEndian));
+    /// ```
+    pub fn bytes_eq(&self, other: &Value, order: ByteOrder) -> bool {
+        match (self.encode(order), other.encode(order)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Encode the value into the bytes an entry would hold.
+    fn encode(&self, order: ByteOrder) -> Result<Vec<u8>, ExifError> {
+        let (components, size, format) = self.get_components_size_format()?;
+        let mut buffer = vec![0u8; components * size];
+
+        let entry = ExifEntry {
+            tag: ExifTag(0),
+            format,
+            components: components as _,
+            data: buffer.as_mut_ptr(),
+            size: buffer.len() as u32,
+            parent: std::ptr::null_mut(),
+            priv_: std::ptr::null_mut(),
+        };
+        self.clone().insert(entry, components, order)?;
+
+        Ok(buffer)
+    }
+
     pub(crate) fn insert(
         self,
         exif_entry: ExifEntry,
@@ -258,6 +995,63 @@ impl Value {
         Ok(())
     }
 
+    /// Number of components, size of a component and format of the entry holding the value.
+    ///
+    /// Text is stored as an `ASCII` entry with a component for the NUL terminator, as the
+    /// EXIF specification defines it:
+    /// ```rust
+    /// use libexif::{Data, DataType, ExifTag, IFD, Value};
+    /// let text = Value::Text("Jane".to_string());
+    /// let (components, size, _) = text.get_components_size_format().unwrap();
+    /// assert_eq!((components, size), (5, 1));
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, text, order).unwrap();
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).unwrap();
+    /// assert_eq!(entry.data_type(), DataType::Text);
+    /// assert_eq!(entry.raw_data(), b"Jane\0");
+    ///
+    /// // Longer text reallocates the entry's data
+    /// data.set_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST, "Jane Doe".into(), order).unwrap();
+    /// let entry = data.get_entry(IFD::Image, ExifTag::EXIF_TAG_ARTIST).unwrap();
+    /// assert_eq!(entry.raw_data(), b"Jane Doe\0");
+    /// ```
+    ///
+    /// The format follows from the variant alone, signed variants are stored with the signed
+    /// formats so they keep their sign when the data is written and read back:
+    /// ```rust
+    /// use libexif::{Data, DataOption, DataReader, DataType, ExifTag, IFD, Rational, Value};
+    /// let values = [
+    ///     (ExifTag::EXIF_TAG_EXPOSURE_BIAS_VALUE, Value::IRational(vec![Rational(-1, 3)])),
+    ///     (ExifTag::EXIF_TAG_BRIGHTNESS_VALUE, Value::I32(vec![-7])),
+    ///     (ExifTag::EXIF_TAG_SHUTTER_SPEED_VALUE, Value::I16(vec![-300])),
+    ///     (ExifTag::EXIF_TAG_APERTURE_VALUE, Value::I8(vec![-2])),
+    /// ];
+    ///
+    /// let mut data = Data::new();
+    /// let order = data.byte_order();
+    /// for (tag, value) in &values {
+    ///     data.set_entry(IFD::EXIF, *tag, value.clone(), order).unwrap();
+    /// }
+    ///
+    /// // Keep libexif from converting the entries to the formats the specification expects
+    /// let tiff = data.raw_exif_block().unwrap();
+    /// let data = DataReader::new()
+    ///     .without_option(DataOption::FollowSpecification)
+    ///     .from_raw_exif_block(&tiff)
+    ///     .unwrap();
+    ///
+    /// for (tag, value) in values {
+    ///     let entry = data.get_entry(IFD::EXIF, tag).unwrap();
+    ///     assert!(matches!(
+    ///         entry.data_type(),
+    ///         DataType::IRational | DataType::I32 | DataType::I16 | DataType::I8
+    ///     ));
+    ///     assert_eq!(entry.data_type(), value.data_type());
+    ///     assert_eq!(entry.value_auto().unwrap(), value);
+    /// }
+    /// ```
     pub fn get_components_size_format(&self) -> Result<(usize, usize, ExifFormat), ExifError> {
         Ok(match self {
             // In case of u8 and i8 vectors the size is 1 * length
@@ -293,8 +1087,8 @@ impl Value {
                     }
                     Ok(())
                 })?;
-                // (data.len() + 3, 1, ExifFormat::EXIF_FORMAT_ASCII)
-                (data.len() + 3, 1, ExifFormat::EXIF_FORMAT_UNDEFINED)
+                // One extra component for the NUL terminator
+                (data.len() + 1, 1, ExifFormat::EXIF_FORMAT_ASCII)
             }
         })
     }
@@ -358,17 +1152,18 @@ fn insert_text(
     )
 }
 
-fn extract_text(raw_data: &[u8], components: usize, byte_order: ByteOrder) -> String {
-    let mut vec = extract_vec::<u8>(raw_data, components, byte_order, get_u8);
-
-    let cstring = unsafe {
-        let len = libc::strlen(vec.as_ptr() as *const c_char);
-        vec.set_len(len);
+fn extract_text(
+    raw_data: &[u8],
+    components: usize,
+    byte_order: ByteOrder,
+    charset: Charset,
+) -> String {
+    let vec = extract_vec::<u8>(raw_data, components, byte_order, get_u8);
 
-        CString::from_vec_unchecked(vec)
-    };
+    // The text ends at the first NUL byte, or at the end of the data if it isn't terminated
+    let len = vec.iter().position(|&b| b == 0).unwrap_or(vec.len());
 
-    cstring.to_string_lossy().into_owned()
+    charset.decode(&vec[..len])
 }
 
 fn extract_vec<T>(
@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+use crate::error::ExifError;
+use crate::jpeg::EXIF_SIGNATURE;
+
+/// Size of the `RIFF` header: the `RIFF` tag, the size of the file and the `WEBP` form type.
+const RIFF_HEADER_SIZE: usize = 12;
+
+/// A chunk of the RIFF container.
+pub(crate) struct Chunk {
+    /// The chunk's FourCC, e.g., `VP8X` or `EXIF`.
+    pub fourcc: [u8; 4],
+
+    /// The chunk's payload.
+    pub payload: Range<usize>,
+
+    /// The whole chunk, including its header and the padding byte of odd-sized payloads.
+    pub range: Range<usize>,
+}
+
+/// Check the `RIFF` header of a WebP file and return its chunks.
+///
+/// Both the simple (lossy `VP8 ` or lossless `VP8L`) and the extended (`VP8X`) layouts are
+/// made of a sequence of chunks, only the extended one can have an `EXIF` chunk though.
+pub(crate) fn chunks(webp: &[u8]) -> Result<Vec<Chunk>, ExifError> {
+    if webp.len() < RIFF_HEADER_SIZE || &webp[..4] != b"RIFF" || &webp[8..12] != b"WEBP" {
+        return Err(ExifError::InvalidWebp("missing RIFF header"));
+    }
+
+    // The size in the header covers the data after the size itself
+    let size = u32::from_le_bytes([webp[4], webp[5], webp[6], webp[7]]) as usize;
+    let end = 8usize
+        .checked_add(size)
+        .filter(|&end| end <= webp.len())
+        .ok_or(ExifError::InvalidWebp("file is truncated"))?;
+
+    let mut chunks = Vec::new();
+    let mut pos = RIFF_HEADER_SIZE;
+    while pos < end {
+        let header = webp
+            .get(pos..pos + 8)
+            .filter(|_| pos + 8 <= end)
+            .ok_or(ExifError::InvalidWebp("truncated chunk header"))?;
+        let fourcc = [header[0], header[1], header[2], header[3]];
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+
+        let payload = pos + 8..pos + 8 + len;
+        if payload.end > end {
+            return Err(ExifError::InvalidWebp("truncated chunk"));
+        }
+
+        // Odd-sized payloads are followed by a padding byte
+        let next = (payload.end + (len & 1)).min(end);
+        chunks.push(Chunk {
+            fourcc,
+            payload,
+            range: pos..next,
+        });
+        pos = next;
+    }
+
+    Ok(chunks)
+}
+
+/// Find the `EXIF` chunk of a WebP file and return it as the payload of an EXIF segment,
+/// starting with the `Exif\0\0` signature so it can be handed to libexif.
+///
+/// The chunk should hold the bare TIFF data, but some writers keep the signature in it.
+pub(crate) fn find_exif(webp: &[u8]) -> Result<Vec<u8>, ExifError> {
+    let chunk = chunks(webp)?
+        .into_iter()
+        .find(|chunk| &chunk.fourcc == b"EXIF")
+        .ok_or(ExifError::InvalidWebp("no EXIF chunk"))?;
+
+    let payload = &webp[chunk.payload];
+    let tiff = payload.strip_prefix(EXIF_SIGNATURE).unwrap_or(payload);
+    if !tiff.starts_with(b"II") && !tiff.starts_with(b"MM") {
+        return Err(ExifError::InvalidWebp("EXIF chunk has no TIFF header"));
+    }
+
+    Ok([EXIF_SIGNATURE, tiff].concat())
+}
+
+/// Flag of the `VP8X` chunk telling the file has an `EXIF` chunk.
+const VP8X_EXIF_FLAG: u8 = 0x08;
+
+/// Flag of the `VP8X` chunk telling the image has an alpha channel.
+const VP8X_ALPHA_FLAG: u8 = 0x10;
+
+/// Put the given TIFF data in the `EXIF` chunk of a WebP file, replacing the existing chunk.
+///
+/// Simple WebP files can't have an `EXIF` chunk, they're converted to the extended layout by
+/// adding a `VP8X` chunk. The EXIF flag of the `VP8X` chunk and the size of the file in the
+/// `RIFF` header are updated.
+pub(crate) fn insert_exif(webp: &[u8], tiff: &[u8]) -> Result<Vec<u8>, ExifError> {
+    let chunks = chunks(webp)?;
+
+    let mut output = webp[..RIFF_HEADER_SIZE].to_vec();
+    if !chunks.iter().any(|chunk| &chunk.fourcc == b"VP8X") {
+        let vp8x = vp8x_for_simple(webp, &chunks)?;
+        write_chunk(&mut output, b"VP8X", &vp8x)?;
+    }
+
+    // The EXIF chunk goes after the image data and before the XMP chunk
+    let position = chunks
+        .iter()
+        .position(|chunk| &chunk.fourcc == b"EXIF" || &chunk.fourcc == b"XMP ")
+        .unwrap_or(chunks.len());
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        if index == position {
+            write_chunk(&mut output, b"EXIF", tiff)?;
+        }
+
+        match &chunk.fourcc {
+            b"EXIF" => {}
+            b"VP8X" => {
+                let start = output.len();
+                output.extend_from_slice(&webp[chunk.range.clone()]);
+                if let Some(flags) = output.get_mut(start + 8) {
+                    *flags |= VP8X_EXIF_FLAG;
+                }
+            }
+            _ => output.extend_from_slice(&webp[chunk.range.clone()]),
+        }
+    }
+    if position == chunks.len() {
+        write_chunk(&mut output, b"EXIF", tiff)?;
+    }
+
+    let size = u32::try_from(output.len() - 8)
+        .map_err(|_| ExifError::MalformedOutput("WebP file is too large"))?;
+    output[4..8].copy_from_slice(&size.to_le_bytes());
+
+    Ok(output)
+}
+
+/// Build the payload of a `VP8X` chunk for a simple WebP file, with the EXIF flag set and the
+/// canvas size read from the `VP8 ` or `VP8L` bitstream.
+fn vp8x_for_simple(webp: &[u8], chunks: &[Chunk]) -> Result<[u8; 10], ExifError> {
+    let image = chunks
+        .iter()
+        .find(|chunk| &chunk.fourcc == b"VP8 " || &chunk.fourcc == b"VP8L")
+        .ok_or(ExifError::InvalidWebp("no image data"))?;
+    let bitstream = &webp[image.payload.clone()];
+
+    let mut flags = VP8X_EXIF_FLAG;
+    let (width, height) = match &image.fourcc {
+        // Lossy: a frame tag, a start code and the 14-bit dimensions with 2 bits of scaling
+        b"VP8 " => {
+            if bitstream.len() < 10 || bitstream[3..6] != [0x9d, 0x01, 0x2a] {
+                return Err(ExifError::InvalidWebp("invalid VP8 bitstream"));
+            }
+
+            let width = u16::from_le_bytes([bitstream[6], bitstream[7]]) & 0x3fff;
+            let height = u16::from_le_bytes([bitstream[8], bitstream[9]]) & 0x3fff;
+            (u32::from(width), u32::from(height))
+        }
+        // Lossless: a signature and the 14-bit dimensions minus one, followed by the alpha hint
+        _ => {
+            if bitstream.len() < 5 || bitstream[0] != 0x2f {
+                return Err(ExifError::InvalidWebp("invalid VP8L bitstream"));
+            }
+
+            let bits = u32::from_le_bytes([bitstream[1], bitstream[2], bitstream[3], bitstream[4]]);
+            if bits & (1 << 28) != 0 {
+                flags |= VP8X_ALPHA_FLAG;
+            }
+            ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)
+        }
+    };
+
+    if width == 0 || height == 0 {
+        return Err(ExifError::InvalidWebp("image has no pixels"));
+    }
+
+    // The canvas size is stored minus one on 24 bits
+    let width = (width - 1).to_le_bytes();
+    let height = (height - 1).to_le_bytes();
+
+    Ok([
+        flags, 0, 0, 0, width[0], width[1], width[2], height[0], height[1], height[2],
+    ])
+}
+
+/// Append a chunk with the given FourCC and payload, padded to an even size.
+fn write_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], payload: &[u8]) -> Result<(), ExifError> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| ExifError::MalformedOutput("chunk is too large"))?;
+
+    output.extend_from_slice(fourcc);
+    output.extend_from_slice(&len.to_le_bytes());
+    output.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        output.push(0);
+    }
+
+    Ok(())
+}
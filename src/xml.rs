@@ -0,0 +1,93 @@
+use std::ffi::CStr;
+use std::fmt::Write;
+
+use crate::bindings::*;
+use crate::data::Data;
+use crate::internal::*;
+
+impl Data {
+    /// Render all the entries as XML, one `<tag>` element per entry.
+    ///
+    /// Each element carries the libexif names of the IFD, the tag and the data format, and
+    /// contains the value as formatted by
+    /// [`Entry::text_value()`](struct.Entry.html#method.text_value), like `exif --xml` does:
+    ///
+    /// ```xml
+    /// <exif>
+    ///   <tag ifd="0" name="Orientation" type="Short">Top-left</tag>
+    /// </exif>
+    /// ```
+    ///
+    /// Tags unknown to libexif are named after their ID, e.g. `0xc4a5`.
+    ///
+    /// # Example
+    /// ```rust
+    /// let data = libexif::Data::open("assets/file.jpg").unwrap();
+    /// let xml = data.to_xml();
+    ///
+    /// assert!(xml.starts_with("<exif>\n"));
+    /// assert!(xml.contains(r#"<tag ifd="0" name="XResolution" type="Rational">"#));
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<exif>\n");
+
+        for content in self.contents() {
+            let ifd = c_name(unsafe { exif_ifd_get_name(content.ifd().to_libexif()) });
+
+            for entry in content.entries() {
+                let tag = entry.tag();
+                let name = if entry.is_known_tag() {
+                    tag.name(content.ifd()).to_string()
+                } else {
+                    format!("0x{:04x}", tag.id())
+                };
+                let format =
+                    c_name(unsafe { exif_format_get_name(entry.data_type().to_libexif()) });
+                let text = entry.text_value().unwrap_or_default();
+
+                let _ = writeln!(
+                    xml,
+                    r#"  <tag ifd="{}" name="{}" type="{}">{}</tag>"#,
+                    escape(ifd),
+                    escape(&name),
+                    escape(format),
+                    escape(&text)
+                );
+            }
+        }
+
+        xml.push_str("</exif>\n");
+        xml
+    }
+}
+
+/// Convert a name returned by libexif, which is null for unknown values.
+fn c_name(ptr: *const libc::c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap_or("")
+}
+
+/// Escape text for use in XML content and attribute values.
+///
+/// Control characters aren't allowed in XML 1.0 documents, they're replaced by U+FFFD.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
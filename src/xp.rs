@@ -0,0 +1,94 @@
+use crate::bindings::ExifTag;
+use crate::bits::*;
+use crate::data::Data;
+use crate::error::ExifError;
+use crate::value::{decode_utf16, Value};
+
+macro_rules! xp_tags {
+    (
+        $(
+            $name: ident => $tag: ident, $title: literal,
+        )*
+    ) => {
+        $(
+            paste! {
+                #[doc = concat!("Returns the Windows ", $title, " (stored as UTF-16LE in IFD0).")]
+                pub fn [<xp_$name>](&self) -> Option<String> {
+                    self.get_xp(ExifTag::$tag)
+                }
+
+                #[doc = concat!("Sets the Windows ", $title, " (stored as UTF-16LE in IFD0).")]
+                pub fn [<set_xp_$name>](&mut self, text: &str) -> Result<(), ExifError> {
+                    self.set_xp(ExifTag::$tag, text)
+                }
+            }
+        )*
+    };
+}
+
+/// Windows Explorer stores its title, comment, author, keywords and subject fields as
+/// NUL-terminated UTF-16LE strings in `BYTE` tags of the image IFD.
+///
+/// libexif doesn't record these tags in any IFD, so it would drop them when loading with
+/// `IgnoreUnknownTags` and when fixing the data. The crate keeps them in both cases.
+///
+/// # Example
+/// ```rust
+/// use libexif::Data;
+/// let mut data = Data::new();
+/// data.set_xp_title("Sunset über dem Meer").unwrap();
+/// assert_eq!(data.xp_title().as_deref(), Some("Sunset über dem Meer"));
+///
+/// // The tags survive writing, which fixes the data, and reading the file back
+/// let mut data = Data::open("assets/file.jpg").unwrap();
+/// data.set_xp_title("Sunset über dem Meer").unwrap();
+/// data.set_xp_keywords("sea;sunset").unwrap();
+/// data.write_from_file("assets/file.jpg", "/tmp/xp_tags.jpg").unwrap();
+///
+/// let data = Data::open("/tmp/xp_tags.jpg").unwrap();
+/// assert_eq!(data.xp_title().as_deref(), Some("Sunset über dem Meer"));
+/// assert_eq!(data.xp_keywords().as_deref(), Some("sea;sunset"));
+/// ```
+impl Data {
+    xp_tags! {
+        title => EXIF_TAG_XP_TITLE, "XPTitle",
+        comment => EXIF_TAG_XP_COMMENT, "XPComment",
+        author => EXIF_TAG_XP_AUTHOR, "XPAuthor",
+        keywords => EXIF_TAG_XP_KEYWORDS, "XPKeywords",
+        subject => EXIF_TAG_XP_SUBJECT, "XPSubject",
+    }
+
+    fn get_xp(&self, tag: ExifTag) -> Option<String> {
+        let entry = self.get_entry(IFD::Image, tag).ok()?;
+
+        Some(decode_utf16(entry.raw_data(), ByteOrder::LittleEndian))
+    }
+
+    fn set_xp(&mut self, tag: ExifTag, text: &str) -> Result<(), ExifError> {
+        let order = self.byte_order();
+
+        // libexif doesn't record the XP tags in any IFD so skip the tag table check
+        self.set_entry_unchecked(IFD::Image, tag, Value::U8(encode_utf16le(text)), order)
+    }
+}
+
+/// The XP tags, which libexif doesn't record in any IFD.
+pub(crate) const XP_TAGS: [ExifTag; 5] = [
+    ExifTag::EXIF_TAG_XP_TITLE,
+    ExifTag::EXIF_TAG_XP_COMMENT,
+    ExifTag::EXIF_TAG_XP_AUTHOR,
+    ExifTag::EXIF_TAG_XP_KEYWORDS,
+    ExifTag::EXIF_TAG_XP_SUBJECT,
+];
+
+/// Whether the tag is one of the [`XP_TAGS`].
+pub(crate) fn is_xp_tag(tag: ExifTag) -> bool {
+    XP_TAGS.contains(&tag)
+}
+
+fn encode_utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}